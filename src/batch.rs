@@ -0,0 +1,107 @@
+use crate::secp256k1::{get_context, PublicKey, Signature};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+// ECDSA can't be batch verified algebraically, so instead we spread the single verifications over `threads` workers.
+// All the workers share the global context (and its precomputed generator table).
+// Returns a result per item, `None` means the item wasn't checked because `abort_on_failure`
+// was set and some other item already failed.
+pub fn verify_many(
+    items: &[(&PublicKey, &[u8], &Signature)],
+    to_hash: bool,
+    threads: usize,
+    abort_on_failure: bool,
+) -> Vec<Option<bool>> {
+    let mut results = vec![None; items.len()];
+    if items.is_empty() {
+        return results;
+    }
+    // Initialize the context once here instead of racing for it in the workers.
+    get_context();
+
+    let threads = threads.max(1).min(items.len());
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+
+    let verified: Vec<Vec<(usize, bool)>> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut res = Vec::new();
+                    loop {
+                        if abort_on_failure && failed.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        if i >= items.len() {
+                            break;
+                        }
+                        let (pubkey, msg, sig) = items[i];
+                        let valid = pubkey.verify_ref(msg, sig, to_hash);
+                        if !valid {
+                            failed.store(true, Ordering::Relaxed);
+                        }
+                        res.push((i, valid));
+                    }
+                    res
+                })
+            })
+            .collect();
+        workers.into_iter().map(|worker| worker.join().unwrap()).collect()
+    });
+
+    for (i, valid) in verified.into_iter().flatten() {
+        results[i] = Some(valid);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::HashTrait;
+    use crate::secp256k1::PrivateKey;
+
+    fn sign_many(amount: u32) -> (Vec<PublicKey>, Vec<[u8; 32]>, Vec<Signature>) {
+        let mut pubkeys = Vec::new();
+        let mut msgs = Vec::new();
+        let mut sigs = Vec::new();
+        for i in 0..amount {
+            let privkey = PrivateKey::from_serialized(&i.to_be_bytes().hash_digest());
+            let msg = format!("message number {}", i).as_bytes().hash_digest();
+            sigs.push(privkey.sign(&msg, false));
+            pubkeys.push(privkey.generate_pubkey());
+            msgs.push(msg);
+        }
+        (pubkeys, msgs, sigs)
+    }
+
+    #[test]
+    fn test_verify_many() {
+        let (pubkeys, mut msgs, sigs) = sign_many(8);
+        msgs[5][0] ^= 1;
+        let items: Vec<_> = (0..8).map(|i| (&pubkeys[i], &msgs[i][..], &sigs[i])).collect();
+
+        let results = verify_many(&items, false, 3, false);
+        let expected: Vec<_> = (0..8).map(|i| Some(i != 5)).collect();
+        assert_eq!(results, expected);
+        assert_eq!(verify_many(&items, false, 1, false), expected);
+        assert!(verify_many(&[], false, 4, true).is_empty());
+    }
+
+    #[test]
+    fn test_verify_many_abort() {
+        let (pubkeys, mut msgs, sigs) = sign_many(6);
+        msgs[0][0] ^= 1;
+        let items: Vec<_> = (0..6).map(|i| (&pubkeys[i], &msgs[i][..], &sigs[i])).collect();
+
+        // With a single worker nothing after the first failure gets checked.
+        let results = verify_many(&items, false, 1, true);
+        assert_eq!(results[0], Some(false));
+        assert!(results[1..].iter().all(Option::is_none));
+
+        let results = verify_many(&items, false, 4, true);
+        assert_eq!(results[0], Some(false));
+        assert!(results[1..].iter().all(|res| res != &Some(false)));
+    }
+}
@@ -0,0 +1,247 @@
+use crate::hash::HashTrait;
+use crate::point::Point;
+use crate::secp256k1::{get_context, PrivateKey, PublicKey};
+use crate::utility::{bytes_to_hex, int_from_bytes, int_to_bytes, scalar_from_seed};
+use rug::Integer;
+
+// Blind Diffie-Hellman key exchange, the ecash primitives of Cashu NUT-00 with the DLEQ proofs of NUT-12.
+// The user maps a secret x to Y = hash_to_curve(x) and sends the mint B' = Y + r*G. The mint with key k signs it
+// blindly, C' = k*B', and the user unblinds it with the mint's public key K: C = C' - r*K = k*Y.
+// Later the mint checks C = k*hash_to_curve(x) without being able to link it to the B' it signed.
+
+const DOMAIN_SEPARATOR: &[u8] = b"Secp256k1_HashToCurve_Cashu_";
+
+// Y = 02 || SHA256(SHA256(domain || x) || counter), for the first counter that gives a point on the curve.
+pub fn hash_to_curve(secret: &[u8]) -> Result<PublicKey, &'static str> {
+    let msg_hash = [DOMAIN_SEPARATOR, secret].concat().hash_digest();
+    for counter in 0u32..1 << 16 {
+        let x = [&msg_hash[..], &counter.to_le_bytes()].concat().hash_digest();
+        if let Ok(point) = PublicKey::from_compressed(&[&[0x02][..], &x].concat()) {
+            return Ok(point);
+        }
+    }
+    Err("No point on the curve found for the secret")
+}
+
+// B' = Y + r*G, `r` is the blinding factor the user keeps.
+pub fn blind_message(secret: &[u8], r: &PrivateKey) -> Result<PublicKey, &'static str> {
+    let y = hash_to_curve(secret)?;
+    Ok((Point::from(y) + get_context().mul_generator(&r.scalar)).into())
+}
+
+// The mint's blind signature C' = k*B'.
+pub fn sign_blinded(k: &PrivateKey, blinded: &PublicKey) -> PublicKey {
+    (&k.scalar * Point::from(blinded.clone())).into()
+}
+
+// C = C' - r*K, where K is the mint's public key.
+pub fn unblind_signature(blinded_sig: &PublicKey, r: &PrivateKey, mint_pubkey: &PublicKey) -> PublicKey {
+    let minus_r = Integer::from(&get_context().order - &r.scalar);
+    (Point::from(blinded_sig.clone()) + minus_r * Point::from(mint_pubkey.clone())).into()
+}
+
+// The mint's check of a token: C = k*hash_to_curve(x).
+pub fn verify(k: &PrivateKey, secret: &[u8], sig: &PublicKey) -> bool {
+    match hash_to_curve(secret) {
+        Ok(y) => sign_blinded(k, &y) == *sig,
+        Err(_) => false,
+    }
+}
+
+// A NUT-12 proof that the blind signature C' = k*B' was made with the key of K = k*G,
+// so the mint can't tag users by signing with a different key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DleqProof {
+    e: [u8; 32],
+    s: [u8; 32],
+}
+
+impl DleqProof {
+    // `rand` must be fresh randomness.
+    #[allow(non_snake_case)]
+    pub fn prove(k: &PrivateKey, blinded: &PublicKey, rand: &[u8; 32]) -> DleqProof {
+        let secp = get_context();
+        let order = &secp.order;
+        let A = k.generate_pubkey();
+        let C_ = sign_blinded(k, blinded);
+        let seed = [&rand[..], &k.serialize(), &blinded.compressed()].concat();
+        let r = scalar_from_seed(b"BDHKE/DLEQ nonce", &seed);
+        let R1 = PublicKey::from(secp.mul_generator(&r));
+        let R2 = sign_blinded(&PrivateKey::new(r.clone()), blinded);
+        let e = hash_e(&[&R1, &R2, &A, &C_]);
+        let s = (r + int_from_bytes(&e) * &k.scalar) % order;
+        DleqProof { e, s: int_to_bytes(&s) }
+    }
+
+    pub fn new(e: [u8; 32], s: [u8; 32]) -> DleqProof {
+        DleqProof { e, s }
+    }
+
+    pub fn e(&self) -> [u8; 32] {
+        self.e
+    }
+
+    pub fn s(&self) -> [u8; 32] {
+        self.s
+    }
+
+    // The user's check of the blind signature C' of B' under the mint key A.
+    #[allow(non_snake_case)]
+    pub fn verify(&self, mint_pubkey: &PublicKey, blinded: &PublicKey, blinded_sig: &PublicKey) -> bool {
+        let secp = get_context();
+        let (e, s) = (int_from_bytes(&self.e), int_from_bytes(&self.s));
+        if e >= secp.order || s >= secp.order {
+            return false;
+        }
+        let minus_e = Integer::from(&secp.order - &e);
+        // R1 = s*G - e*A, R2 = s*B' - e*C'
+        let R1 = secp.mul_generator(&s) + &minus_e * Point::from(mint_pubkey.clone());
+        let R2 = &s * Point::from(blinded.clone()) + &minus_e * Point::from(blinded_sig.clone());
+        if R1.is_on_infinity() || R2.is_on_infinity() {
+            return false;
+        }
+        hash_e(&[&R1.into(), &R2.into(), mint_pubkey, blinded_sig]) == self.e
+    }
+
+    // The check by somebody who received the unblinded token (x, C) and the blinding factor r:
+    // it rebuilds B' = Y + r*G and C' = C + r*A.
+    pub fn verify_unblinded(&self, mint_pubkey: &PublicKey, secret: &[u8], sig: &PublicKey, r: &PrivateKey) -> bool {
+        let blinded = match blind_message(secret, r) {
+            Ok(blinded) => blinded,
+            Err(_) => return false,
+        };
+        let blinded_sig = Point::from(sig.clone()) + &r.scalar * Point::from(mint_pubkey.clone());
+        if blinded_sig.is_on_infinity() {
+            return false;
+        }
+        self.verify(mint_pubkey, &blinded, &blinded_sig.into())
+    }
+}
+
+// e = SHA256 of the lowercase hex of the uncompressed points, as NUT-12 defines it.
+fn hash_e(points: &[&PublicKey]) -> [u8; 32] {
+    let hex: String = points.iter().map(|point| bytes_to_hex(&point.uncompressed()).to_lowercase()).collect();
+    hex.as_bytes().hash_digest()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utility::hex_to_bytes;
+
+    fn pubkey(hex: &str) -> PublicKey {
+        PublicKey::from_compressed(&hex_to_bytes(hex)).unwrap()
+    }
+
+    #[test]
+    fn test_hash_to_curve_vectors() {
+        let vectors = [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "024cce997d3b518f739663b757deaec95bcd9473c30a14ac2fd04023a739d1a725",
+            ),
+            (
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "022e7158e11c9506f1aa4248bf531298daa7febd6194f003edcd9b93ade6253acf",
+            ),
+            (
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "026cdbe15362df59cd1dd3c9c11de8aedac2106eca69236ecd9fbe117af897be4f",
+            ),
+        ];
+        for (secret, expected) in vectors.iter() {
+            assert_eq!(hash_to_curve(&hex_to_bytes(secret)).unwrap(), pubkey(expected));
+        }
+    }
+
+    #[test]
+    fn test_nut_vectors() {
+        // NUT-00 blinded message and blinded signature.
+        let r = PrivateKey::from_serialized(&hex_to_bytes("0000000000000000000000000000000000000000000000000000000000000001"));
+        let blinded = blind_message(b"test_message", &r).unwrap();
+        assert_eq!(blinded, pubkey("025cc16fe33b953e2ace39653efb3e7a7049711ae1d8a2f7a9108753f1cdea742b"));
+        let blinded = pubkey("02a9acc1e48c25eeeb9289b5031cc57da9fe72f3fe2861d264bdc074209b107ba2");
+        assert_eq!(sign_blinded(&r, &blinded), blinded);
+        let k = PrivateKey::from_serialized(&[0x7f; 32]);
+        assert_eq!(sign_blinded(&k, &blinded), pubkey("0398bc70ce8184d27ba89834d19f5199c84443c31131e48d3c1214db24247d005d"));
+
+        // NUT-12 hash_e.
+        let one = pubkey("020000000000000000000000000000000000000000000000000000000000000001");
+        let e = hex_to_bytes("a4dc034b74338c28c6bc3ea49731f2a24440fc7c4affc08b31a93fc9fbe6401e");
+        assert_eq!(hash_e(&[&one, &one, &one, &blinded])[..], e[..]);
+    }
+
+    #[test]
+    fn test_nut12_dleq_vectors() {
+        let bytes32 = |hex: &str| {
+            let mut res = [0u8; 32];
+            res.copy_from_slice(&hex_to_bytes(hex));
+            res
+        };
+        let mint_pubkey = pubkey("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+
+        // The DLEQ of a BlindedSignature: B', C', e and s.
+        let blinded = pubkey("02a9acc1e48c25eeeb9289b5031cc57da9fe72f3fe2861d264bdc074209b107ba2");
+        let blinded_sig = pubkey("02a9acc1e48c25eeeb9289b5031cc57da9fe72f3fe2861d264bdc074209b107ba2");
+        let proof = DleqProof::new(
+            bytes32("9818e061ee51d5c8edc3342369a554998ff7b4381c8652d724cdf46429be73d9"),
+            bytes32("9818e061ee51d5c8edc3342369a554998ff7b4381c8652d724cdf46429be73da"),
+        );
+        assert!(proof.verify(&mint_pubkey, &blinded, &blinded_sig));
+        assert!(!proof.verify(&blinded, &blinded, &blinded_sig));
+
+        // The DLEQ of a Proof, which also carries the blinding factor r.
+        let secret = b"daf4dd00a2b68a0858a80450f52c8a7d2ccf87d375e43e216e0c571f089f63e9";
+        let sig = pubkey("024369d2d22a80ecf78f3937da9d5f30c1b9f74f0c32684d583cca0fa6a61cdcfc");
+        let r = PrivateKey::from_serialized(&hex_to_bytes("a6d13fcd7a18442e6076f5e1e7c887ad5de40a019824bdfa9fe740d302e8d861"));
+        let proof = DleqProof::new(
+            bytes32("b31e58ac6527f34975ffab13e70a48b6d2b0d35abc4b03f0151f09ee1a9763d4"),
+            bytes32("8fbae004c59e754d71df67e392b6ae4e29293113ddc2ec86592a0431d16306d8"),
+        );
+        assert!(proof.verify_unblinded(&mint_pubkey, secret, &sig, &r));
+        assert!(!proof.verify_unblinded(&mint_pubkey, &secret[1..], &sig, &r));
+        assert!(!proof.verify_unblinded(&mint_pubkey, secret, &sig, &PrivateKey::from_serialized(&[1; 32])));
+    }
+
+    #[test]
+    fn test_bdhke() {
+        let k = PrivateKey::from_serialized(&[0x11; 32]);
+        let mint_pubkey = k.generate_pubkey();
+        let r = PrivateKey::from_serialized(&[0x22; 32]);
+        let secret = b"407915bc212be61a77e3e6d2aeb4c727980bda51cd06a6afc29e2861768a7837";
+
+        let blinded = blind_message(secret, &r).unwrap();
+        let blinded_sig = sign_blinded(&k, &blinded);
+        let proof = DleqProof::prove(&k, &blinded, &[1; 32]);
+        assert!(proof.verify(&mint_pubkey, &blinded, &blinded_sig));
+
+        let sig = unblind_signature(&blinded_sig, &r, &mint_pubkey);
+        assert_eq!(sig, sign_blinded(&k, &hash_to_curve(secret).unwrap()));
+        assert!(verify(&k, secret, &sig));
+        assert!(!verify(&k, b"other secret", &sig));
+        assert!(!verify(&PrivateKey::from_serialized(&[0x33; 32]), secret, &sig));
+        assert!(proof.verify_unblinded(&mint_pubkey, secret, &sig, &r));
+        assert!(!proof.verify_unblinded(&mint_pubkey, b"other secret", &sig, &r));
+    }
+
+    #[test]
+    fn test_invalid_dleq() {
+        let k = PrivateKey::from_serialized(&[0x11; 32]);
+        let other = PrivateKey::from_serialized(&[0x33; 32]);
+        let blinded = blind_message(b"secret", &PrivateKey::from_serialized(&[0x22; 32])).unwrap();
+        let blinded_sig = sign_blinded(&k, &blinded);
+        let proof = DleqProof::prove(&k, &blinded, &[1; 32]);
+
+        // The proof is bound to the mint key.
+        assert!(!proof.verify(&other.generate_pubkey(), &blinded, &blinded_sig));
+        // Signing with another key than the published one is caught.
+        let tagged = sign_blinded(&other, &blinded);
+        assert!(!DleqProof::prove(&other, &blinded, &[1; 32]).verify(&k.generate_pubkey(), &blinded, &tagged));
+        assert!(!proof.verify(&k.generate_pubkey(), &blinded, &tagged));
+
+        let mut s = proof.s();
+        s[31] ^= 1;
+        assert!(!DleqProof::new(proof.e(), s).verify(&k.generate_pubkey(), &blinded, &blinded_sig));
+        assert!(!DleqProof::new(proof.e(), [0xff; 32]).verify(&k.generate_pubkey(), &blinded, &blinded_sig));
+    }
+}
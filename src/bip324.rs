@@ -0,0 +1,409 @@
+use crate::chacha20::{aead_decrypt, aead_encrypt, chacha20_block, TAG_LEN};
+use crate::ellswift::ellswift_xdh;
+use crate::hmac_sha2::Hkdf;
+use crate::secp256k1::PrivateKey;
+
+// The packet layer of the BIP324 v2 P2P transport, without any sockets: it turns message contents into encrypted
+// packets and back over byte buffers.
+// A packet is the encrypted 3 byte length of the contents, followed by the AEAD encryption of a header byte and the
+// contents. Both ciphers rekey themselves every 224 messages, so old traffic stays secret if the keys leak later.
+// Before the first packet each side sends up to 4095 bytes of garbage and a 16 byte garbage terminator,
+// and the garbage is authenticated as the associated data of the first packet.
+
+pub const REKEY_INTERVAL: u64 = 224;
+pub const LENGTH_LEN: usize = 3;
+pub const HEADER_LEN: usize = 1;
+// The bytes a packet adds to the contents.
+pub const EXPANSION: usize = LENGTH_LEN + HEADER_LEN + TAG_LEN;
+pub const GARBAGE_TERMINATOR_LEN: usize = 16;
+pub const MAX_GARBAGE_LEN: usize = 4095;
+const MAX_CONTENTS_LEN: usize = (1 << 24) - 1;
+// Packets with this bit of the header set are decoys, the receiver drops them.
+const IGNORE_BIT: u8 = 0x80;
+
+// FSChaCha20, the cipher of the length field. It's a ChaCha20 stream over all the chunks,
+// and after every REKEY_INTERVAL chunks the next 32 bytes of the stream are the new key.
+pub struct FsChaCha20 {
+    key: [u8; 32],
+    chunk_counter: u64,
+    rekey_counter: u64,
+    block_counter: u32,
+    keystream: Vec<u8>,
+}
+
+impl FsChaCha20 {
+    pub fn new(key: [u8; 32]) -> FsChaCha20 {
+        FsChaCha20 { key, chunk_counter: 0, rekey_counter: 0, block_counter: 0, keystream: Vec::new() }
+    }
+
+    fn keystream(&mut self, len: usize) -> Vec<u8> {
+        while self.keystream.len() < len {
+            let mut nonce = [0u8; 12];
+            nonce[4..].copy_from_slice(&self.rekey_counter.to_le_bytes());
+            self.keystream.extend_from_slice(&chacha20_block(&self.key, &nonce, self.block_counter));
+            self.block_counter += 1;
+        }
+        self.keystream.drain(..len).collect()
+    }
+
+    // Encryption and decryption are the same.
+    pub fn crypt(&mut self, chunk: &[u8]) -> Vec<u8> {
+        let res = chunk.iter().zip(self.keystream(chunk.len())).map(|(byte, k)| byte ^ k).collect();
+        self.chunk_counter += 1;
+        if self.chunk_counter % REKEY_INTERVAL == 0 {
+            let new_key = self.keystream(32);
+            self.key.copy_from_slice(&new_key);
+            self.rekey_counter += 1;
+            self.block_counter = 0;
+            self.keystream.clear();
+        }
+        res
+    }
+}
+
+// FSChaCha20Poly1305, the AEAD of the packets. The nonce is the packet number, and after every REKEY_INTERVAL
+// packets the new key is the keystream of the nonce 0xffffffff || rekey counter.
+pub struct FsChaCha20Poly1305 {
+    key: [u8; 32],
+    packet_counter: u64,
+}
+
+impl FsChaCha20Poly1305 {
+    pub fn new(key: [u8; 32]) -> FsChaCha20Poly1305 {
+        FsChaCha20Poly1305 { key, packet_counter: 0 }
+    }
+
+    fn nonce(&self) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[..4].copy_from_slice(&((self.packet_counter % REKEY_INTERVAL) as u32).to_le_bytes());
+        nonce[4..].copy_from_slice(&(self.packet_counter / REKEY_INTERVAL).to_le_bytes());
+        nonce
+    }
+
+    fn next_packet(&mut self) {
+        self.packet_counter += 1;
+        if self.packet_counter % REKEY_INTERVAL == 0 {
+            let mut nonce = [0xffu8; 12];
+            nonce[4..].copy_from_slice(&(self.packet_counter / REKEY_INTERVAL - 1).to_le_bytes());
+            let new_key = chacha20_block(&self.key, &nonce, 1);
+            self.key.copy_from_slice(&new_key[..32]);
+        }
+    }
+
+    pub fn encrypt(&mut self, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let res = aead_encrypt(&self.key, &self.nonce(), aad, plaintext);
+        self.next_packet();
+        res
+    }
+
+    // A packet that fails to decrypt still uses up its nonce.
+    pub fn decrypt(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let res = aead_decrypt(&self.key, &self.nonce(), aad, ciphertext);
+        self.next_packet();
+        res
+    }
+}
+
+// A decrypted packet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Packet {
+    pub contents: Vec<u8>,
+    pub ignore: bool,
+}
+
+// The ciphers of one side of a connection.
+pub struct Cipher {
+    send_l: FsChaCha20,
+    send_p: FsChaCha20Poly1305,
+    recv_l: FsChaCha20,
+    recv_p: FsChaCha20Poly1305,
+    send_garbage_terminator: [u8; GARBAGE_TERMINATOR_LEN],
+    recv_garbage_terminator: [u8; GARBAGE_TERMINATOR_LEN],
+    session_id: [u8; 32],
+    // The length of the packet `decode` is waiting for.
+    recv_len: Option<usize>,
+}
+
+impl Cipher {
+    // Derive the keys from the ElligatorSwift key exchange, `our_ellswift` is the encoding of our key that we sent
+    // (`ellswift_create`). `network_magic` is the message start of the network, f9beb4d9 on mainnet.
+    pub fn new(
+        sk: &PrivateKey,
+        our_ellswift: &[u8; 64],
+        their_ellswift: &[u8; 64],
+        initiating: bool,
+        network_magic: &[u8; 4],
+    ) -> Result<Cipher, &'static str> {
+        let (ell_a, ell_b) = if initiating { (our_ellswift, their_ellswift) } else { (their_ellswift, our_ellswift) };
+        let shared_secret = ellswift_xdh(ell_a, ell_b, sk, initiating)?;
+        Ok(Cipher::from_shared_secret(&shared_secret, initiating, network_magic))
+    }
+
+    fn from_shared_secret(shared_secret: &[u8; 32], initiating: bool, network_magic: &[u8; 4]) -> Cipher {
+        let hkdf = Hkdf::extract(&[&b"bitcoin_v2_shared_secret"[..], network_magic].concat(), shared_secret);
+        let key = |label: &[u8]| {
+            let mut key = [0u8; 32];
+            key.copy_from_slice(&hkdf.expand(label, 32).unwrap());
+            key
+        };
+        let (initiator_l, initiator_p) = (key(b"initiator_L"), key(b"initiator_P"));
+        let (responder_l, responder_p) = (key(b"responder_L"), key(b"responder_P"));
+        let terminators = key(b"garbage_terminators");
+        let (mut initiator_terminator, mut responder_terminator) = ([0u8; 16], [0u8; 16]);
+        initiator_terminator.copy_from_slice(&terminators[..16]);
+        responder_terminator.copy_from_slice(&terminators[16..]);
+
+        let (send, recv) = if initiating {
+            ((initiator_l, initiator_p, initiator_terminator), (responder_l, responder_p, responder_terminator))
+        } else {
+            ((responder_l, responder_p, responder_terminator), (initiator_l, initiator_p, initiator_terminator))
+        };
+        Cipher {
+            send_l: FsChaCha20::new(send.0),
+            send_p: FsChaCha20Poly1305::new(send.1),
+            recv_l: FsChaCha20::new(recv.0),
+            recv_p: FsChaCha20Poly1305::new(recv.1),
+            send_garbage_terminator: send.2,
+            recv_garbage_terminator: recv.2,
+            session_id: key(b"session_id"),
+            recv_len: None,
+        }
+    }
+
+    // Both sides get the same session id, which can be compared out of band to detect a man in the middle.
+    pub fn session_id(&self) -> [u8; 32] {
+        self.session_id
+    }
+
+    pub fn send_garbage_terminator(&self) -> [u8; GARBAGE_TERMINATOR_LEN] {
+        self.send_garbage_terminator
+    }
+
+    pub fn recv_garbage_terminator(&self) -> [u8; GARBAGE_TERMINATOR_LEN] {
+        self.recv_garbage_terminator
+    }
+
+    // The length of the garbage at the start of `buffer` once its terminator arrived, so the first packet starts
+    // GARBAGE_TERMINATOR_LEN bytes after it.
+    pub fn find_garbage_len(&self, buffer: &[u8]) -> Result<Option<usize>, &'static str> {
+        let searched = &buffer[..buffer.len().min(MAX_GARBAGE_LEN + GARBAGE_TERMINATOR_LEN)];
+        match searched.windows(GARBAGE_TERMINATOR_LEN).position(|window| window == self.recv_garbage_terminator) {
+            Some(len) => Ok(Some(len)),
+            None if searched.len() == MAX_GARBAGE_LEN + GARBAGE_TERMINATOR_LEN => {
+                Err("There's no garbage terminator after the maximum garbage length")
+            }
+            None => Ok(None),
+        }
+    }
+
+    // Encrypt a packet. `aad` is the garbage we sent for the first packet and empty for the rest.
+    pub fn encrypt(&mut self, contents: &[u8], aad: &[u8], ignore: bool) -> Result<Vec<u8>, &'static str> {
+        if contents.len() > MAX_CONTENTS_LEN {
+            return Err("The contents don't fit in the length field");
+        }
+        let len = (contents.len() as u32).to_le_bytes();
+        let mut res = self.send_l.crypt(&len[..LENGTH_LEN]);
+        let header = if ignore { IGNORE_BIT } else { 0 };
+        res.extend(self.send_p.encrypt(aad, &[&[header], contents].concat()));
+        Ok(res)
+    }
+
+    // The length of the contents of the next packet, which is followed by HEADER_LEN + len + TAG_LEN more bytes.
+    pub fn decrypt_length(&mut self, input: &[u8; LENGTH_LEN]) -> usize {
+        let len = self.recv_l.crypt(input);
+        len.iter().rev().fold(0, |acc, byte| acc << 8 | *byte as usize)
+    }
+
+    // Decrypt the rest of the packet after its length.
+    pub fn decrypt(&mut self, input: &[u8], aad: &[u8]) -> Result<Packet, &'static str> {
+        let plaintext = self.recv_p.decrypt(aad, input)?;
+        if plaintext.is_empty() {
+            return Err("The packet has no header");
+        }
+        Ok(Packet { contents: plaintext[HEADER_LEN..].to_vec(), ignore: plaintext[0] & IGNORE_BIT != 0 })
+    }
+
+    // Decode the packet at the start of `buffer`, returns it with the number of bytes it took.
+    // Returns None until the whole packet arrived, then call it again with the same start of the buffer.
+    pub fn decode(&mut self, buffer: &[u8], aad: &[u8]) -> Result<Option<(Packet, usize)>, &'static str> {
+        if buffer.len() < LENGTH_LEN {
+            return Ok(None);
+        }
+        let len = match self.recv_len {
+            Some(len) => len,
+            None => {
+                let mut input = [0u8; LENGTH_LEN];
+                input.copy_from_slice(&buffer[..LENGTH_LEN]);
+                let len = self.decrypt_length(&input);
+                self.recv_len = Some(len);
+                len
+            }
+        };
+        let total = len + EXPANSION;
+        if buffer.len() < total {
+            return Ok(None);
+        }
+        self.recv_len = None;
+        let packet = self.decrypt(&buffer[LENGTH_LEN..total], aad)?;
+        Ok(Some((packet, total)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chacha20::chacha20_xor;
+    use crate::ellswift::ellswift_create;
+    use crate::test_vectors::BIP324_PACKET_VECTORS;
+    use crate::utility::hex_to_bytes;
+    use std::convert::TryInto;
+
+    fn handshake() -> (Cipher, Cipher) {
+        let (sk_a, sk_b) = (PrivateKey::from_serialized(&[1; 32]), PrivateKey::from_serialized(&[2; 32]));
+        let ell_a = ellswift_create(&sk_a, Some(&[3; 32])).unwrap();
+        let ell_b = ellswift_create(&sk_b, Some(&[4; 32])).unwrap();
+        let magic = [0xf9, 0xbe, 0xb4, 0xd9];
+        let initiator = Cipher::new(&sk_a, &ell_a, &ell_b, true, &magic).unwrap();
+        let responder = Cipher::new(&sk_b, &ell_b, &ell_a, false, &magic).unwrap();
+        (initiator, responder)
+    }
+
+    #[test]
+    fn test_packet_vectors() {
+        let magic = [0xf9, 0xbe, 0xb4, 0xd9];
+        for vector in BIP324_PACKET_VECTORS.iter() {
+            let sk = PrivateKey::from_serialized(&hex_to_bytes(vector.priv_ours));
+            let ours: [u8; 64] = hex_to_bytes(vector.ellswift_ours)[..].try_into().unwrap();
+            let theirs: [u8; 64] = hex_to_bytes(vector.ellswift_theirs)[..].try_into().unwrap();
+            let mut cipher = Cipher::new(&sk, &ours, &theirs, vector.initiating, &magic).unwrap();
+            if let Some(session_id) = vector.session_id {
+                assert_eq!(cipher.session_id()[..], hex_to_bytes(session_id)[..]);
+            }
+
+            // The other side gets the same shared secret, so it can decode what we send.
+            let (ell_a, ell_b) = if vector.initiating { (&ours, &theirs) } else { (&theirs, &ours) };
+            let shared_secret = ellswift_xdh(ell_a, ell_b, &sk, vector.initiating).unwrap();
+            let mut peer = Cipher::from_shared_secret(&shared_secret, !vector.initiating, &magic);
+            assert_eq!(peer.session_id(), cipher.session_id());
+            assert_eq!(peer.recv_garbage_terminator(), cipher.send_garbage_terminator());
+
+            for _ in 0..vector.idx {
+                let dummy = cipher.encrypt(&[], &[], false).unwrap();
+                assert!(peer.decode(&dummy, &[]).unwrap().unwrap().0.contents.is_empty());
+            }
+            let contents = hex_to_bytes(vector.contents).repeat(vector.multiply);
+            let aad = hex_to_bytes(vector.aad);
+            let ciphertext = cipher.encrypt(&contents, &aad, vector.ignore).unwrap();
+            assert_eq!(ciphertext.len(), contents.len() + EXPANSION);
+            if vector.ciphertext.is_empty() {
+                assert!(ciphertext.ends_with(&hex_to_bytes(vector.ciphertext_endswith)));
+            } else {
+                assert_eq!(ciphertext, hex_to_bytes(vector.ciphertext));
+            }
+            let (packet, len) = peer.decode(&ciphertext, &aad).unwrap().unwrap();
+            assert_eq!(packet, Packet { contents, ignore: vector.ignore });
+            assert_eq!(len, ciphertext.len());
+        }
+    }
+
+    #[test]
+    fn test_fschacha20_rekey() {
+        let key = [7u8; 32];
+        let mut cipher = FsChaCha20::new(key);
+        // Before the rekey it's a single ChaCha20 stream.
+        let stream: Vec<u8> = (0..11).flat_map(|i| chacha20_block(&key, &[0; 12], i).to_vec()).collect();
+        for i in 0..REKEY_INTERVAL as usize {
+            assert_eq!(cipher.crypt(&[0; 3]), stream[3 * i..3 * i + 3]);
+        }
+        // The new key is the next 32 bytes, with the rekey counter in the nonce.
+        let mut new_key = [0u8; 32];
+        new_key.copy_from_slice(&stream[3 * 224..3 * 224 + 32]);
+        let mut nonce = [0u8; 12];
+        nonce[4] = 1;
+        assert_eq!(cipher.crypt(&[0; 3]), chacha20_block(&new_key, &nonce, 0)[..3]);
+    }
+
+    #[test]
+    fn test_fschacha20poly1305_rekey() {
+        let key = [9u8; 32];
+        let mut cipher = FsChaCha20Poly1305::new(key);
+        for i in 0..REKEY_INTERVAL as u32 {
+            let mut nonce = [0u8; 12];
+            nonce[..4].copy_from_slice(&i.to_le_bytes());
+            assert_eq!(cipher.encrypt(b"aad", b"msg"), aead_encrypt(&key, &nonce, b"aad", b"msg"));
+        }
+        let mut new_key = [0u8; 32];
+        chacha20_xor(&key, &[0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0], 1, &mut new_key);
+        let mut nonce = [0u8; 12];
+        nonce[4] = 1;
+        assert_eq!(cipher.encrypt(b"", b"msg"), aead_encrypt(&new_key, &nonce, b"", b"msg"));
+
+        let mut decrypter = FsChaCha20Poly1305::new(key);
+        let ciphertext = FsChaCha20Poly1305::new(key).encrypt(b"", b"msg");
+        assert!(decrypter.decrypt(b"", &ciphertext).is_ok());
+        // The nonce moved on.
+        assert!(decrypter.decrypt(b"", &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_packets() {
+        let (mut initiator, mut responder) = handshake();
+        assert_eq!(initiator.session_id(), responder.session_id());
+        assert_eq!(initiator.send_garbage_terminator(), responder.recv_garbage_terminator());
+        assert_eq!(initiator.recv_garbage_terminator(), responder.send_garbage_terminator());
+        assert_ne!(initiator.send_garbage_terminator(), initiator.recv_garbage_terminator());
+
+        // The garbage, its terminator and the first packet, which authenticates the garbage.
+        let garbage = vec![0x55; 100];
+        let mut stream = [&garbage[..], &initiator.send_garbage_terminator()].concat();
+        stream.extend(initiator.encrypt(b"version", &garbage, false).unwrap());
+        assert_eq!(responder.find_garbage_len(&stream[..50]), Ok(None));
+        let garbage_len = responder.find_garbage_len(&stream).unwrap().unwrap();
+        assert_eq!(garbage_len, garbage.len());
+        let stream = &stream[garbage_len + GARBAGE_TERMINATOR_LEN..];
+        let (packet, len) = responder.decode(stream, &garbage).unwrap().unwrap();
+        assert_eq!(packet, Packet { contents: b"version".to_vec(), ignore: false });
+        assert_eq!(len, stream.len());
+
+        // Enough packets for a couple of rekeys, in both directions.
+        for i in 0..500usize {
+            let contents = vec![i as u8; i % 70];
+            let packet = responder.encrypt(&contents, &[], i % 5 == 0).unwrap();
+            assert_eq!(packet.len(), contents.len() + EXPANSION);
+            let (decoded, len) = initiator.decode(&packet, &[]).unwrap().unwrap();
+            assert_eq!(decoded, Packet { contents: contents.clone(), ignore: i % 5 == 0 });
+            assert_eq!(len, packet.len());
+
+            let packet = initiator.encrypt(&contents, &[], false).unwrap();
+            let mut input = [0u8; LENGTH_LEN];
+            input.copy_from_slice(&packet[..LENGTH_LEN]);
+            assert_eq!(responder.decrypt_length(&input), contents.len());
+            assert_eq!(responder.decrypt(&packet[LENGTH_LEN..], &[]).unwrap().contents, contents);
+        }
+    }
+
+    #[test]
+    fn test_partial_and_invalid_packets() {
+        let (mut initiator, mut responder) = handshake();
+        let packet = initiator.encrypt(b"hello", &[], false).unwrap();
+        assert_eq!(responder.decode(&packet[..2], &[]), Ok(None));
+        assert_eq!(responder.decode(&packet[..10], &[]), Ok(None));
+        assert_eq!(responder.decode(&packet, &[]).unwrap().unwrap().0.contents, b"hello");
+
+        let mut packet = initiator.encrypt(b"hello", &[], false).unwrap();
+        packet[5] ^= 1;
+        assert!(responder.decode(&packet, &[]).is_err());
+
+        // The wrong garbage fails the first packet.
+        let (mut initiator, mut responder) = handshake();
+        let packet = initiator.encrypt(b"version", b"garbage", false).unwrap();
+        assert!(responder.decode(&packet, b"other garbage").is_err());
+        assert!(initiator.encrypt(&vec![0; 1 << 24], &[], false).is_err());
+
+        // Too much garbage.
+        assert!(responder.find_garbage_len(&[0u8; MAX_GARBAGE_LEN + GARBAGE_TERMINATOR_LEN]).is_err());
+        let terminator = responder.recv_garbage_terminator();
+        let stream = [&[0u8; MAX_GARBAGE_LEN][..], &terminator].concat();
+        assert_eq!(responder.find_garbage_len(&stream), Ok(Some(MAX_GARBAGE_LEN)));
+    }
+}
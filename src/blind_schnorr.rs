@@ -0,0 +1,217 @@
+use crate::hash::HashTrait;
+use crate::point::Point;
+use crate::secp256k1::{get_context, PrivateKey, PublicKey, SchnorrSignature};
+use crate::utility::{int_from_bytes, int_to_bytes, scalar_from_seed, zeroize};
+use rug::Integer;
+use std::collections::BTreeMap;
+
+// Blind Schnorr signatures: the signer signs a message it never sees, and can't link the final signature to the
+// session it came from. The signatures are the ones `PrivateKey::sign_schnorr` makes, so they verify with
+// `PublicKey::verify_schnorr`.
+//
+// 1. The signer commits to a nonce R = k*G (`BlindSigner::commit`).
+// 2. The user blinds it with random alpha and beta: R' = R + alpha*G + beta*P, and sends the challenge
+//    e = hash(R'.x || P || m) + beta (`blind`).
+// 3. The signer answers with s = k + e*x (`BlindSigner::respond`).
+// 4. The user unblinds it into the signature (R'.x, s + alpha) (`Unblinder::unblind`).
+//
+// With many sessions open at the same time the user can solve the ROS problem and get one more signature than
+// the sessions it finished. With more than 256 concurrent sessions that takes polynomial time (Benhamouda et al.),
+// with l of them Wagner's algorithm takes about 2^(256 / (1 + log2(l + 1))) work: 2^128 for one session, 2^85 for
+// three, 2^64 for seven. So the signer limits how many can be open at once, at most `MAX_SESSIONS`, and every nonce
+// is only ever used for one response. One session at a time keeps the full 128-bit security.
+
+// The most concurrent sessions a signer allows, which keeps the ROS attack above 2^80 work.
+pub const MAX_SESSIONS: usize = 3;
+
+pub struct BlindSigner {
+    sk: PrivateKey,
+    max_sessions: usize,
+    // The nonces of the open sessions.
+    sessions: BTreeMap<u64, [u8; 32]>,
+    next_session: u64,
+}
+
+impl BlindSigner {
+    // `max_sessions` is the number of sessions that can be open at the same time, in [1, MAX_SESSIONS].
+    pub fn new(sk: PrivateKey, max_sessions: usize) -> Result<BlindSigner, &'static str> {
+        if sk.scalar == 0 || sk.scalar >= get_context().order {
+            return Err("The private key is not in the range [1, n-1]");
+        }
+        if max_sessions == 0 {
+            return Err("At least one session has to be allowed");
+        }
+        if max_sessions > MAX_SESSIONS {
+            return Err("Too many concurrent sessions to be safe against the ROS attack");
+        }
+        Ok(BlindSigner { sk, max_sessions, sessions: BTreeMap::new(), next_session: 0 })
+    }
+
+    pub fn pubkey(&self) -> PublicKey {
+        self.sk.generate_pubkey()
+    }
+
+    pub fn open_sessions(&self) -> usize {
+        self.sessions.len()
+    }
+
+    // Open a session, returns its id and the nonce commitment R. `rand` must be fresh randomness.
+    pub fn commit(&mut self, rand: &[u8; 32]) -> Result<(u64, PublicKey), &'static str> {
+        let secp = get_context();
+        if self.sessions.len() >= self.max_sessions {
+            return Err("Too many concurrent sessions");
+        }
+        let session = self.next_session;
+        let seed = [&rand[..], &self.sk.serialize(), &session.to_be_bytes()].concat();
+        let k = scalar_from_seed(b"BlindSchnorr/nonce", &seed);
+        let r = PublicKey::from(secp.mul_generator(&k));
+        self.sessions.insert(session, int_to_bytes(&k));
+        self.next_session += 1;
+        Ok((session, r))
+    }
+
+    // Sign the blinded challenge, which closes the session.
+    pub fn respond(&mut self, session: u64, challenge: &[u8; 32]) -> Result<[u8; 32], &'static str> {
+        let order = &get_context().order;
+        let e = int_from_bytes(challenge);
+        if e >= *order {
+            return Err("The challenge overflows the curve order");
+        }
+        let mut k = self.sessions.remove(&session).ok_or("There is no such open session")?;
+        let s = (int_from_bytes(&k) + e * &self.sk.scalar) % order;
+        zeroize(&mut k);
+        Ok(int_to_bytes(&s))
+    }
+
+    // Close a session without signing, e.g. after a timeout.
+    pub fn abort(&mut self, session: u64) {
+        if let Some(mut k) = self.sessions.remove(&session) {
+            zeroize(&mut k);
+        }
+    }
+}
+
+impl Drop for BlindSigner {
+    fn drop(&mut self) {
+        self.sessions.values_mut().for_each(|k| zeroize(k));
+    }
+}
+
+// What the user keeps to unblind the signer's response.
+pub struct Unblinder {
+    pubkey: PublicKey,
+    r: PublicKey,
+    challenge: Integer,
+    alpha: Integer,
+    r_prime: PublicKey,
+}
+
+// Blind the signer's nonce commitment `r` for signing `msg_hash`, returns the challenge to send to the signer.
+// `rand` must be fresh randomness, alpha and beta are what keeps the signature unlinkable.
+#[allow(non_snake_case)]
+pub fn blind(pubkey: &PublicKey, r: &PublicKey, msg_hash: &[u8; 32], rand: &[u8; 32]) -> Result<([u8; 32], Unblinder), &'static str> {
+    let secp = get_context();
+    let order = &secp.order;
+    let (P, R) = (Point::from(pubkey.clone()), Point::from(r.clone()));
+    if P.is_on_infinity() || R.is_on_infinity() {
+        return Err("The points can't be the point at infinity");
+    }
+    // `verify_schnorr` wants R' with a square y, which alpha and beta decide, so retry until it has one.
+    for attempt in 0u32.. {
+        let seed = [&rand[..], msg_hash, &attempt.to_be_bytes()].concat();
+        let alpha = scalar_from_seed(b"BlindSchnorr/alpha", &seed);
+        let beta = scalar_from_seed(b"BlindSchnorr/beta", &seed);
+        let R_prime = R.clone() + secp.mul_generator(&alpha) + &beta * P.clone();
+        if R_prime.is_on_infinity() {
+            continue;
+        }
+        let R_prime = PublicKey::from(R_prime);
+        if !R_prime.is_square_y() {
+            continue;
+        }
+        // The challenge `verify_schnorr` uses: hash(R'.x || P || m).
+        let e = int_from_bytes(&[&R_prime.x_only()[..], &pubkey.compressed(), msg_hash].concat().hash_digest());
+        let challenge = (e % order + beta) % order;
+        let unblinder = Unblinder { pubkey: pubkey.clone(), r: r.clone(), challenge: challenge.clone(), alpha, r_prime: R_prime };
+        return Ok((int_to_bytes(&challenge), unblinder));
+    }
+    unreachable!()
+}
+
+impl Unblinder {
+    // Check the signer's response and turn it into the signature of the message.
+    pub fn unblind(self, response: &[u8; 32]) -> Result<SchnorrSignature, &'static str> {
+        let secp = get_context();
+        let order = &secp.order;
+        let s = int_from_bytes(response);
+        if s >= *order {
+            return Err("The response overflows the curve order");
+        }
+        if secp.mul_generator(&s) != Point::from(self.r.clone()) + &self.challenge * Point::from(self.pubkey.clone()) {
+            return Err("The signer's response is invalid");
+        }
+        let s = (s + &self.alpha) % order;
+        Ok(SchnorrSignature::new(&self.r_prime.x_only(), &int_to_bytes(&s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blind_schnorr() {
+        let mut signer = BlindSigner::new(PrivateKey::from_serialized(&[1; 32]), MAX_SESSIONS).unwrap();
+        let pubkey = signer.pubkey();
+        for i in 0..6u8 {
+            let msg = [i; 32];
+            let (session, r) = signer.commit(&[i; 32]).unwrap();
+            let (challenge, unblinder) = blind(&pubkey, &r, &msg, &[i + 100; 32]).unwrap();
+            let response = signer.respond(session, &challenge).unwrap();
+            let sig = unblinder.unblind(&response).unwrap().serialize();
+            assert!(pubkey.verify_schnorr(&msg, SchnorrSignature::parse(sig), false));
+            assert!(!pubkey.verify_schnorr(&[i + 1; 32], SchnorrSignature::parse(sig), false));
+            // The signer never saw the final R or s.
+            assert_ne!(sig[..32], r.x_only());
+            assert_ne!(sig[32..], response);
+        }
+        assert_eq!(signer.open_sessions(), 0);
+    }
+
+    #[test]
+    fn test_session_limit() {
+        let mut signer = BlindSigner::new(PrivateKey::from_serialized(&[2; 32]), 2).unwrap();
+        let pubkey = signer.pubkey();
+        let (first, r) = signer.commit(&[1; 32]).unwrap();
+        let (second, _) = signer.commit(&[2; 32]).unwrap();
+        assert_eq!(signer.commit(&[3; 32]).err(), Some("Too many concurrent sessions"));
+
+        signer.abort(second);
+        assert_eq!(signer.open_sessions(), 1);
+        assert!(signer.respond(second, &[1; 32]).is_err());
+        let (third, _) = signer.commit(&[3; 32]).unwrap();
+        assert_ne!(third, second);
+
+        // A nonce is only used once.
+        let (challenge, unblinder) = blind(&pubkey, &r, &[7; 32], &[4; 32]).unwrap();
+        let response = signer.respond(first, &challenge).unwrap();
+        assert_eq!(signer.respond(first, &challenge).err(), Some("There is no such open session"));
+        assert!(unblinder.unblind(&response).is_ok());
+        assert!(signer.respond(third, &[0xff; 32]).is_err());
+        assert!(BlindSigner::new(PrivateKey::from_serialized(&[2; 32]), 0).is_err());
+        assert!(BlindSigner::new(PrivateKey::from_serialized(&[2; 32]), MAX_SESSIONS + 1).is_err());
+        assert!(BlindSigner::new(PrivateKey::from_serialized(&[2; 32]), usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_invalid_response() {
+        let mut signer = BlindSigner::new(PrivateKey::from_serialized(&[3; 32]), 1).unwrap();
+        let pubkey = signer.pubkey();
+        let (session, r) = signer.commit(&[1; 32]).unwrap();
+        let (challenge, unblinder) = blind(&pubkey, &r, &[7; 32], &[2; 32]).unwrap();
+        let mut response = signer.respond(session, &challenge).unwrap();
+        response[31] ^= 1;
+        assert_eq!(unblinder.unblind(&response).err(), Some("The signer's response is invalid"));
+        assert!(blind(&pubkey, &PublicKey::zero_pubkey(), &[7; 32], &[2; 32]).is_err());
+    }
+}
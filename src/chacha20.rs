@@ -0,0 +1,183 @@
+use rug::{integer::Order, Integer};
+
+// ChaCha20, Poly1305 and the ChaCha20Poly1305 AEAD of RFC 8439.
+
+pub(crate) const TAG_LEN: usize = 16;
+
+const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+#[inline(always)]
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+pub(crate) fn chacha20_block(key: &[u8; 32], nonce: &[u8; 12], counter: u32) -> [u8; 64] {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&CONSTANTS);
+    for i in 0..8 {
+        state[4 + i] = le_u32(&key[4 * i..]);
+    }
+    state[12] = counter;
+    for i in 0..3 {
+        state[13 + i] = le_u32(&nonce[4 * i..]);
+    }
+    let mut working = state;
+    for _ in 0..10 {
+        quarter_round(&mut working, 0, 4, 8, 12);
+        quarter_round(&mut working, 1, 5, 9, 13);
+        quarter_round(&mut working, 2, 6, 10, 14);
+        quarter_round(&mut working, 3, 7, 11, 15);
+        quarter_round(&mut working, 0, 5, 10, 15);
+        quarter_round(&mut working, 1, 6, 11, 12);
+        quarter_round(&mut working, 2, 7, 8, 13);
+        quarter_round(&mut working, 3, 4, 9, 14);
+    }
+    let mut res = [0u8; 64];
+    for (i, (word, initial)) in working.iter().zip(&state).enumerate() {
+        res[4 * i..4 * i + 4].copy_from_slice(&word.wrapping_add(*initial).to_le_bytes());
+    }
+    res
+}
+
+// Encrypt or decrypt in place, starting at block `counter`.
+pub(crate) fn chacha20_xor(key: &[u8; 32], nonce: &[u8; 12], counter: u32, data: &mut [u8]) {
+    for (i, chunk) in data.chunks_mut(64).enumerate() {
+        let keystream = chacha20_block(key, nonce, counter.wrapping_add(i as u32));
+        chunk.iter_mut().zip(keystream.iter()).for_each(|(byte, k)| *byte ^= k);
+    }
+}
+
+// The one-time authenticator: the message as 16-byte little-endian numbers is evaluated as a polynomial
+// at the clamped r mod 2^130 - 5, and s is added.
+pub(crate) fn poly1305(key: &[u8; 32], msg: &[u8]) -> [u8; TAG_LEN] {
+    let p = (Integer::from(1) << 130) - 5;
+    let mut r = key[..16].to_vec();
+    for i in &[3, 7, 11, 15] {
+        r[*i] &= 15;
+    }
+    for i in &[4, 8, 12] {
+        r[*i] &= 252;
+    }
+    let r = Integer::from_digits(&r, Order::Lsf);
+    let s = Integer::from_digits(&key[16..], Order::Lsf);
+
+    let mut acc = Integer::new();
+    for chunk in msg.chunks(16) {
+        let n = Integer::from_digits(&[chunk, &[1]].concat(), Order::Lsf);
+        acc = (acc + n) * &r % &p;
+    }
+    acc += s;
+    acc.keep_bits_mut(128);
+    let digits = acc.to_digits::<u8>(Order::Lsf);
+    let mut tag = [0u8; TAG_LEN];
+    tag[..digits.len()].copy_from_slice(&digits);
+    tag
+}
+
+fn aead_tag(key: &[u8; 32], nonce: &[u8; 12], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_LEN] {
+    let mut otk = [0u8; 32];
+    otk.copy_from_slice(&chacha20_block(key, nonce, 0)[..32]);
+    let pad = |len: usize| vec![0u8; (16 - len % 16) % 16];
+    let mac_data = [
+        aad,
+        &pad(aad.len()),
+        ciphertext,
+        &pad(ciphertext.len()),
+        &(aad.len() as u64).to_le_bytes(),
+        &(ciphertext.len() as u64).to_le_bytes(),
+    ]
+    .concat();
+    poly1305(&otk, &mac_data)
+}
+
+// Returns the ciphertext followed by the 16 byte tag.
+pub(crate) fn aead_encrypt(key: &[u8; 32], nonce: &[u8; 12], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    let mut res = plaintext.to_vec();
+    chacha20_xor(key, nonce, 1, &mut res);
+    let tag = aead_tag(key, nonce, aad, &res);
+    res.extend_from_slice(&tag);
+    res
+}
+
+pub(crate) fn aead_decrypt(key: &[u8; 32], nonce: &[u8; 12], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, &'static str> {
+    if ciphertext.len() < TAG_LEN {
+        return Err("The ciphertext is too short for the tag");
+    }
+    let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_LEN);
+    let expected = aead_tag(key, nonce, aad, ciphertext);
+    // Compare all the bytes, so the time doesn't depend on where they differ.
+    if expected.iter().zip(tag).fold(0, |acc, (a, b)| acc | (a ^ b)) != 0 {
+        return Err("The tag doesn't match");
+    }
+    let mut res = ciphertext.to_vec();
+    chacha20_xor(key, nonce, 1, &mut res);
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utility::hex_to_bytes;
+    use std::convert::TryInto;
+
+    const SUNSCREEN: &[u8] =
+        b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+    fn key(hex: &str) -> [u8; 32] {
+        hex_to_bytes(hex)[..].try_into().unwrap()
+    }
+
+    fn nonce(hex: &str) -> [u8; 12] {
+        hex_to_bytes(hex)[..].try_into().unwrap()
+    }
+
+    // RFC 8439 sections 2.3.2, 2.4.2, 2.5.2 and 2.8.2.
+    #[test]
+    fn test_chacha20() {
+        let k = key("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+        let block = chacha20_block(&k, &nonce("000000090000004a00000000"), 1);
+        let expected = "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4ed2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e";
+        assert_eq!(block[..], hex_to_bytes(expected)[..]);
+
+        let mut data = SUNSCREEN.to_vec();
+        chacha20_xor(&k, &nonce("000000000000004a00000000"), 1, &mut data);
+        let expected = "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0bf91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d807ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab77937365af90bbf74a35be6b40b8eedf2785e42874d";
+        assert_eq!(data, hex_to_bytes(expected));
+    }
+
+    #[test]
+    fn test_poly1305() {
+        let k = key("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b");
+        let tag = poly1305(&k, b"Cryptographic Forum Research Group");
+        assert_eq!(tag[..], hex_to_bytes("a8061dc1305136c6c22b8baf0c0127a9")[..]);
+    }
+
+    #[test]
+    fn test_aead() {
+        let k = key("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+        let n = nonce("070000004041424344454647");
+        let aad = hex_to_bytes("50515253c0c1c2c3c4c5c6c7");
+        let ciphertext = aead_encrypt(&k, &n, &aad, SUNSCREEN);
+        let expected = "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d63dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b3692ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc3ff4def08e4b7a9de576d26586cec64b6116";
+        assert_eq!(ciphertext[..SUNSCREEN.len()], hex_to_bytes(expected)[..]);
+        assert_eq!(ciphertext[SUNSCREEN.len()..], hex_to_bytes("1ae10b594f09e26a7e902ecbd0600691")[..]);
+        assert_eq!(aead_decrypt(&k, &n, &aad, &ciphertext).unwrap(), SUNSCREEN);
+
+        let mut tampered = ciphertext.clone();
+        tampered[0] ^= 1;
+        assert!(aead_decrypt(&k, &n, &aad, &tampered).is_err());
+        assert!(aead_decrypt(&k, &n, b"other aad", &ciphertext).is_err());
+        assert!(aead_decrypt(&k, &n, &aad, &ciphertext[..15]).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use crate::point::Point;
+use crate::secp256k1::{get_context, PrivateKey, PublicKey};
+use crate::utility::{int_from_bytes, int_to_bytes, tagged_hash};
+use rug::Integer;
+
+// BIP374 discrete log equality proofs: a proof that A = a*G and C = a*B for the same secret a,
+// without revealing a. Used by silent payments to prove an ECDH share is correct.
+// An optional 32 byte message can be bound to the proof.
+
+// Prove that log_G(a*G) = log_B(a*B). `aux_rand` is mixed into the nonce like the BIP340 auxiliary randomness.
+#[allow(non_snake_case)]
+pub fn dleq_prove(
+    a: &PrivateKey,
+    B: &PublicKey,
+    aux_rand: &[u8; 32],
+    G: &PublicKey,
+    msg: Option<&[u8; 32]>,
+) -> Result<[u8; 64], &'static str> {
+    let order = &get_context().order;
+    if a.scalar == 0 || a.scalar >= *order {
+        return Err("The secret is not in the range [1, n-1]");
+    }
+    if Point::from(B.clone()).is_on_infinity() || Point::from(G.clone()).is_on_infinity() {
+        return Err("The points can't be the point at infinity");
+    }
+    let A = PublicKey::from(&a.scalar * Point::from(G.clone()));
+    let C = PublicKey::from(&a.scalar * Point::from(B.clone()));
+    let msg_bytes: &[u8] = msg.map_or(&[], |msg| &msg[..]);
+
+    let mut t = tagged_hash(b"BIP0374/aux", aux_rand);
+    t.iter_mut().zip(int_to_bytes(&a.scalar).iter()).for_each(|(byte, a)| *byte ^= a);
+    let k = int_from_bytes(&tagged_hash(b"BIP0374/nonce", &[&t[..], &A.compressed(), &C.compressed(), msg_bytes].concat())) % order;
+    if k == 0 {
+        return Err("The nonce is zero");
+    }
+    let R1 = PublicKey::from(&k * Point::from(G.clone()));
+    let R2 = PublicKey::from(&k * Point::from(B.clone()));
+    let e = dleq_challenge(&A, B, &C, G, &R1, &R2, msg_bytes);
+    let s = (k + Integer::from(&e * &a.scalar)) % order;
+
+    let mut proof = [0u8; 64];
+    proof[..32].copy_from_slice(&int_to_bytes(&e));
+    proof[32..].copy_from_slice(&int_to_bytes(&s));
+    // BIP374 checks the proof before returning it.
+    if !dleq_verify(&A, B, &C, &proof, G, msg) {
+        return Err("The proof doesn't verify");
+    }
+    Ok(proof)
+}
+
+// Verify a proof that log_G(A) = log_B(C).
+#[allow(non_snake_case)]
+pub fn dleq_verify(A: &PublicKey, B: &PublicKey, C: &PublicKey, proof: &[u8; 64], G: &PublicKey, msg: Option<&[u8; 32]>) -> bool {
+    let order = &get_context().order;
+    let points = [A, B, C, G].iter().map(|p| Point::from((*p).clone())).collect::<Vec<_>>();
+    if points.iter().any(Point::is_on_infinity) {
+        return false;
+    }
+    let e = int_from_bytes(&proof[..32]);
+    let s = int_from_bytes(&proof[32..]);
+    if s >= *order {
+        return false;
+    }
+    let minus_e = order - Integer::from(&e % order);
+    // R1 = s*G - e*A, R2 = s*B - e*C
+    let R1 = &s * points[3].clone() + &minus_e * points[0].clone();
+    let R2 = &s * points[1].clone() + &minus_e * points[2].clone();
+    if R1.is_on_infinity() || R2.is_on_infinity() {
+        return false;
+    }
+    let msg: &[u8] = msg.map_or(&[], |msg| &msg[..]);
+    dleq_challenge(A, B, C, G, &R1.into(), &R2.into(), msg) == e
+}
+
+// e = int(hash_BIP0374/challenge(A || B || C || G || R1 || R2 || m)), it's not reduced mod n.
+#[allow(non_snake_case)]
+fn dleq_challenge(A: &PublicKey, B: &PublicKey, C: &PublicKey, G: &PublicKey, R1: &PublicKey, R2: &PublicKey, msg: &[u8]) -> Integer {
+    let points: Vec<_> = [A, B, C, G, R1, R2].iter().flat_map(|p| p.compressed().to_vec()).collect();
+    int_from_bytes(&tagged_hash(b"BIP0374/challenge", &[&points[..], msg].concat()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generator() -> PublicKey {
+        get_context().generator().into()
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_dleq() {
+        let G = generator();
+        for i in 1..=4u8 {
+            let a = PrivateKey::from_serialized(&[i; 32]);
+            let B = PrivateKey::from_serialized(&[i + 0x40; 32]).generate_pubkey();
+            let (A, C) = (a.generate_pubkey(), PublicKey::from(&a.scalar * Point::from(B.clone())));
+            let msg = [i; 32];
+
+            let proof = dleq_prove(&a, &B, &[i; 32], &G, None).unwrap();
+            assert!(dleq_verify(&A, &B, &C, &proof, &G, None));
+            assert!(!dleq_verify(&A, &B, &C, &proof, &G, Some(&msg)));
+            assert_ne!(dleq_prove(&a, &B, &[0; 32], &G, None).unwrap(), proof);
+
+            let proof = dleq_prove(&a, &B, &[i; 32], &G, Some(&msg)).unwrap();
+            assert!(dleq_verify(&A, &B, &C, &proof, &G, Some(&msg)));
+            assert!(!dleq_verify(&A, &B, &C, &proof, &G, None));
+            assert!(!dleq_verify(&A, &B, &C, &proof, &G, Some(&[0; 32])));
+
+            // Any generator works, A is a*G for that generator.
+            let H = PrivateKey::from_serialized(&[i + 0x80; 32]).generate_pubkey();
+            let proof = dleq_prove(&a, &B, &[i; 32], &H, None).unwrap();
+            assert!(dleq_verify(&PublicKey::from(&a.scalar * Point::from(H.clone())), &B, &C, &proof, &H, None));
+            assert!(!dleq_verify(&A, &B, &C, &proof, &H, None));
+        }
+    }
+
+    // The proof rebuilt byte by byte from the definitions in BIP374:
+    // t = bytes(a) xor hash_BIP0374/aux(r), k = int(hash_BIP0374/nonce(t || cbytes(A) || cbytes(C) || m)) mod n,
+    // e = hash_BIP0374/challenge(cbytes(A) || cbytes(B) || cbytes(C) || cbytes(G) || cbytes(k*G) || cbytes(k*B) || m),
+    // s = (k + e*a) mod n, and the proof is e || s. Without a message m is empty.
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_proof_layout() {
+        let secp = get_context();
+        let G = generator();
+        let a = PrivateKey::from_serialized(&[0x5a; 32]);
+        let B = PrivateKey::from_serialized(&[0xa5; 32]).generate_pubkey();
+        let (A, C) = (a.generate_pubkey(), PublicKey::from(&a.scalar * Point::from(B.clone())));
+        let aux_rand = [0x3c; 32];
+        for msg in [None, Some([0x4d; 32])].iter() {
+            let m: &[u8] = msg.as_ref().map_or(&[], |msg| &msg[..]);
+            let aux = tagged_hash(b"BIP0374/aux", &aux_rand);
+            let mut nonce_input: Vec<u8> = a.serialize().iter().zip(aux.iter()).map(|(a, r)| a ^ r).collect();
+            nonce_input.extend_from_slice(&A.compressed());
+            nonce_input.extend_from_slice(&C.compressed());
+            nonce_input.extend_from_slice(m);
+            let k = int_from_bytes(&tagged_hash(b"BIP0374/nonce", &nonce_input)) % &secp.order;
+            let R1 = PublicKey::from(secp.mul_generator(&k));
+            let R2 = PublicKey::from(&k * Point::from(B.clone()));
+
+            let mut challenge_input = Vec::new();
+            for point in [&A, &B, &C, &G, &R1, &R2].iter() {
+                challenge_input.extend_from_slice(&point.compressed());
+            }
+            challenge_input.extend_from_slice(m);
+            let e = tagged_hash(b"BIP0374/challenge", &challenge_input);
+            let s = (k + int_from_bytes(&e) * &a.scalar) % &secp.order;
+
+            let proof = dleq_prove(&a, &B, &aux_rand, &G, msg.as_ref()).unwrap();
+            assert_eq!(proof[..32], e[..]);
+            assert_eq!(proof[32..], int_to_bytes(&s)[..]);
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_invalid_dleq() {
+        let G = generator();
+        let a = PrivateKey::from_serialized(&[0x11; 32]);
+        let other = PrivateKey::from_serialized(&[0x22; 32]);
+        let B = PrivateKey::from_serialized(&[0x33; 32]).generate_pubkey();
+        let (A, C) = (a.generate_pubkey(), PublicKey::from(&a.scalar * Point::from(B.clone())));
+        let proof = dleq_prove(&a, &B, &[0; 32], &G, None).unwrap();
+
+        // C has a different discrete log than A.
+        let wrong_c = PublicKey::from(&other.scalar * Point::from(B.clone()));
+        assert!(!dleq_verify(&A, &B, &wrong_c, &proof, &G, None));
+        assert!(!dleq_verify(&other.generate_pubkey(), &B, &C, &proof, &G, None));
+        assert!(!dleq_verify(&A, &other.generate_pubkey(), &C, &proof, &G, None));
+        assert!(!dleq_verify(&A, &B, &C, &dleq_prove(&other, &B, &[0; 32], &G, None).unwrap(), &G, None));
+
+        let mut tampered = proof;
+        tampered[63] ^= 1;
+        assert!(!dleq_verify(&A, &B, &C, &tampered, &G, None));
+        let mut tampered = proof;
+        tampered[0] ^= 1;
+        assert!(!dleq_verify(&A, &B, &C, &tampered, &G, None));
+        // s overflows the order.
+        let mut overflow = proof;
+        overflow[32..].copy_from_slice(&[0xff; 32]);
+        assert!(!dleq_verify(&A, &B, &C, &overflow, &G, None));
+
+        // The secret has to be in [1, n-1] and none of the points can be the point at infinity.
+        let order = get_context().order.clone();
+        assert!(dleq_prove(&PrivateKey::new(0), &B, &[0; 32], &G, None).is_err());
+        assert!(dleq_prove(&PrivateKey::new(order.clone()), &B, &[0; 32], &G, None).is_err());
+        assert!(dleq_prove(&PrivateKey::new(order + 1u32), &B, &[0; 32], &G, None).is_err());
+        assert!(dleq_prove(&a, &PublicKey::zero_pubkey(), &[0; 32], &G, None).is_err());
+        assert!(dleq_prove(&a, &B, &[0; 32], &PublicKey::zero_pubkey(), None).is_err());
+        assert!(!dleq_verify(&PublicKey::zero_pubkey(), &B, &C, &proof, &G, None));
+        assert!(!dleq_verify(&A, &B, &PublicKey::zero_pubkey(), &proof, &G, None));
+        assert!(!dleq_verify(&A, &B, &C, &proof, &PublicKey::zero_pubkey(), None));
+    }
+}
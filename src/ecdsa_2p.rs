@@ -0,0 +1,658 @@
+use crate::paillier::{CorrectKeyProof, PaillierPrivateKey, PaillierPublicKey};
+use crate::point::Point;
+use crate::secp256k1::{get_context, PublicKey, Signature};
+use crate::utility::{int_from_bytes, int_from_seed, int_to_bytes, tagged_hash};
+use rug::{integer::Order, Integer};
+
+// Two-party ECDSA (Lindell 2017). The private key is x = x1*x2, where P1 holds x1 and P2 holds x2,
+// and the public key Q = x1*x2*G is a plain ECDSA key. P1 also owns a Paillier key and P2 keeps c_key = Enc(x1),
+// so P2 can compute an encryption of its half of the signature that only P1 can finish.
+//
+// Key generation takes 7 messages, `KeyGenMsg1` to `KeyGenMsg7`, alternating between P1 and P2:
+// the two key shares with proofs of knowledge of their discrete logs (P1 commits first, so it can't pick x1 after
+// seeing Q2), then P1 proves that its Paillier key is correct and that c_key encrypts the discrete log of Q1,
+// which has to be in range so the plaintexts P2 computes never wrap around the Paillier modulus.
+// Signing takes 4 messages, `SignMsg1` to `SignMsg4`, after which P1 has the signature.
+// Every step takes fresh randomness `rand`.
+
+const PAILLIER_BITS: u32 = 2048;
+// Every round of the range proof halves a cheating P1's chances.
+const RANGE_PROOF_ROUNDS: usize = 128;
+
+// A Schnorr proof of knowledge of x such that Q = x*G (Fiat-Shamir).
+#[derive(Clone, Debug, PartialEq)]
+pub struct DLogProof {
+    r: PublicKey,
+    z: Integer,
+}
+
+impl DLogProof {
+    fn prove(x: &Integer, q: &PublicKey, rand: &[u8; 32]) -> DLogProof {
+        let k = int_from_seed(b"ECDSA2P/dlog nonce", &[&rand[..], &int_to_bytes(x)].concat(), &get_context().order);
+        let r = PublicKey::from(get_context().mul_generator(&k));
+        let z = (k + dlog_challenge(q, &r) * x) % &get_context().order;
+        DLogProof { r, z }
+    }
+
+    pub fn verify(&self, q: &PublicKey) -> bool {
+        get_context().mul_generator(&self.z) == Point::from(self.r.clone()) + dlog_challenge(q, &self.r) * Point::from(q.clone())
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        [&self.r.compressed()[..], &int_to_bytes(&self.z)].concat()
+    }
+}
+
+fn dlog_challenge(q: &PublicKey, r: &PublicKey) -> Integer {
+    int_from_bytes(&tagged_hash(b"ECDSA2P/dlog", &[&q.compressed()[..], &r.compressed()].concat())) % &get_context().order
+}
+
+// A hash commitment, `blind` must be random.
+fn commit(blind: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    tagged_hash(b"ECDSA2P/commit", &[&blind[..], data].concat())
+}
+
+fn commit_point(blind: &[u8; 32], point: &PublicKey, proof: &DLogProof) -> [u8; 32] {
+    commit(blind, &[&point.compressed()[..], &proof.serialize()].concat())
+}
+
+// A random key share and the proof of knowledge for it. P1's share is below n/3 so the
+// PDL check a*x1 + b never wraps around the Paillier modulus.
+fn key_share(tag: &[u8], rand: &[u8; 32], bound: &Integer) -> (Integer, PublicKey, DLogProof) {
+    let x = int_from_seed(tag, rand, &Integer::from(bound - 1)) + 1;
+    let q = PublicKey::from(get_context().mul_generator(&x));
+    let proof = DLogProof::prove(&x, &q, rand);
+    (x, q, proof)
+}
+
+// P1's share is in [1, q/3).
+fn key_share_bound() -> Integer {
+    Integer::from(&get_context().order / 3)
+}
+
+fn blinding(tag: &[u8], rand: &[u8; 32]) -> [u8; 32] {
+    tagged_hash(tag, rand)
+}
+
+// Proves that a Paillier ciphertext c = Enc(x; r) encrypts x in [0, l), or at least in (-l, 2l) which is
+// enough here (the range proof from Lindell 2017, with Fiat-Shamir). Every round P1 encrypts w1 in [l, 2l) and w2 = w1 - l
+// in random order, and depending on the challenge bit either opens both, or opens x + w for the w that puts it in [l, 2l).
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeProof {
+    ciphertexts: Vec<(Integer, Integer)>,
+    responses: Vec<RangeResponse>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum RangeResponse {
+    // Both w's and their randomness.
+    Open { w: (Integer, Integer), r: (Integer, Integer) },
+    // x + w and r * r_w for one of the ciphertexts.
+    Mask { second: bool, z: Integer, s: Integer },
+}
+
+impl RangeProof {
+    fn prove(paillier: &PaillierPublicKey, c: &Integer, x: &Integer, r: &Integer, l: &Integer, rand: &[u8; 32]) -> RangeProof {
+        let n = paillier.n();
+        let mut secrets = Vec::with_capacity(RANGE_PROOF_ROUNDS);
+        let mut ciphertexts = Vec::with_capacity(RANGE_PROOF_ROUNDS);
+        for i in 0..RANGE_PROOF_ROUNDS {
+            let seed = [&rand[..], &(i as u32).to_be_bytes()].concat();
+            let w1 = int_from_seed(b"ECDSA2P/range w", &seed, l) + l;
+            let w2 = Integer::from(&w1 - l);
+            let w = if tagged_hash(b"ECDSA2P/range swap", &seed)[0] & 1 == 1 { (w2, w1) } else { (w1, w2) };
+            let r = (int_from_seed(b"ECDSA2P/range r1", &seed, n), int_from_seed(b"ECDSA2P/range r2", &seed, n));
+            ciphertexts.push((paillier.encrypt(&w.0, &r.0), paillier.encrypt(&w.1, &r.1)));
+            secrets.push((w, r));
+        }
+        let challenge = range_challenge(paillier, c, &ciphertexts);
+        let responses = secrets
+            .into_iter()
+            .enumerate()
+            .map(|(i, (w, r_w))| {
+                if challenge[i / 8] >> (i % 8) & 1 == 0 {
+                    return RangeResponse::Open { w, r: r_w };
+                }
+                let z0 = Integer::from(x + &w.0);
+                let second = !(*l <= z0 && z0 < Integer::from(l * 2));
+                let (z, r_w) = if second { (Integer::from(x + &w.1), r_w.1) } else { (z0, r_w.0) };
+                RangeResponse::Mask { second, z, s: (r_w * r) % n }
+            })
+            .collect();
+        RangeProof { ciphertexts, responses }
+    }
+
+    fn verify(&self, paillier: &PaillierPublicKey, c: &Integer, l: &Integer) -> bool {
+        if self.ciphertexts.len() != RANGE_PROOF_ROUNDS || self.responses.len() != RANGE_PROOF_ROUNDS {
+            return false;
+        }
+        let two_l = Integer::from(l * 2);
+        let challenge = range_challenge(paillier, c, &self.ciphertexts);
+        self.ciphertexts.iter().zip(&self.responses).enumerate().all(|(i, ((c1, c2), response))| {
+            match (challenge[i / 8] >> (i % 8) & 1, response) {
+                (0, RangeResponse::Open { w: (w1, w2), r: (r1, r2) }) => {
+                    let ordered = |low: &Integer, high: &Integer| *low >= 0 && low < l && *high == Integer::from(low + l);
+                    (ordered(w1, w2) || ordered(w2, w1)) && paillier.encrypt(w1, r1) == *c1 && paillier.encrypt(w2, r2) == *c2
+                }
+                (1, RangeResponse::Mask { second, z, s }) => {
+                    let c_w = if *second { c2 } else { c1 };
+                    l <= z && *z < two_l && paillier.encrypt(z, s) == paillier.add(c, c_w)
+                }
+                _ => false,
+            }
+        })
+    }
+}
+
+// One bit per round.
+fn range_challenge(paillier: &PaillierPublicKey, c: &Integer, ciphertexts: &[(Integer, Integer)]) -> [u8; 32] {
+    let mut data = Vec::new();
+    for x in [paillier.n(), c].iter().copied().chain(ciphertexts.iter().flat_map(|(c1, c2)| vec![c1, c2])) {
+        let bytes = x.to_digits::<u8>(Order::MsfBe);
+        data.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+        data.extend_from_slice(&bytes);
+    }
+    tagged_hash(b"ECDSA2P/range", &data)
+}
+
+// P1 -> P2: commitment to Q1 and its proof.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyGenMsg1 {
+    commitment: [u8; 32],
+}
+
+// P2 -> P1: Q2 and its proof.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyGenMsg2 {
+    q2: PublicKey,
+    proof: DLogProof,
+}
+
+// P1 -> P2: opens the commitment, and sends the Paillier key with its proof and c_key = Enc(x1) with its range proof.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyGenMsg3 {
+    q1: PublicKey,
+    proof: DLogProof,
+    blind: [u8; 32],
+    paillier: PaillierPublicKey,
+    paillier_proof: CorrectKeyProof,
+    c_key: Integer,
+    range_proof: RangeProof,
+}
+
+// P2 -> P1: the PDL challenge c' = Enc(a*x1 + b) and a commitment to a and b.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyGenMsg4 {
+    c_prime: Integer,
+    commitment: [u8; 32],
+}
+
+// P1 -> P2: commitment to alpha*G, where alpha = Dec(c').
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyGenMsg5 {
+    commitment: [u8; 32],
+}
+
+// P2 -> P1: opens a and b.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyGenMsg6 {
+    a: Integer,
+    b: Integer,
+    blind: [u8; 32],
+}
+
+// P1 -> P2: opens alpha*G, which has to be a*Q1 + b*G.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyGenMsg7 {
+    q_hat: PublicKey,
+    blind: [u8; 32],
+}
+
+// P1 waiting for `KeyGenMsg2`.
+pub struct P1KeyGen {
+    x1: Integer,
+    q1: PublicKey,
+    proof: DLogProof,
+    blind: [u8; 32],
+}
+
+// P1 waiting for `KeyGenMsg4`.
+pub struct P1KeyGenPdl {
+    share: P1KeyShare,
+}
+
+// P1 waiting for `KeyGenMsg6`.
+pub struct P1KeyGenPdlOpen {
+    share: P1KeyShare,
+    c_prime: Integer,
+    commitment: [u8; 32],
+    alpha: Integer,
+    q_hat: PublicKey,
+    blind: [u8; 32],
+}
+
+// P2 waiting for `KeyGenMsg3`.
+pub struct P2KeyGen {
+    x2: Integer,
+    commitment: [u8; 32],
+}
+
+// P2 waiting for `KeyGenMsg5`.
+pub struct P2KeyGenPdl {
+    share: P2KeyShare,
+    q_prime: PublicKey,
+    a: Integer,
+    b: Integer,
+    blind: [u8; 32],
+}
+
+// P2 waiting for `KeyGenMsg7`.
+pub struct P2KeyGenPdlOpen {
+    share: P2KeyShare,
+    q_prime: PublicKey,
+    commitment: [u8; 32],
+}
+
+// P1's result of the key generation.
+pub struct P1KeyShare {
+    x1: Integer,
+    paillier: PaillierPrivateKey,
+    pubkey: PublicKey,
+}
+
+// P2's result of the key generation.
+pub struct P2KeyShare {
+    x2: Integer,
+    paillier: PaillierPublicKey,
+    c_key: Integer,
+    pubkey: PublicKey,
+}
+
+impl P1KeyShare {
+    pub fn pubkey(&self) -> &PublicKey {
+        &self.pubkey
+    }
+}
+
+impl P2KeyShare {
+    pub fn pubkey(&self) -> &PublicKey {
+        &self.pubkey
+    }
+}
+
+impl P1KeyGen {
+    pub fn new(rand: &[u8; 32]) -> (P1KeyGen, KeyGenMsg1) {
+        let (x1, q1, proof) = key_share(b"ECDSA2P/x1", rand, &key_share_bound());
+        let blind = blinding(b"ECDSA2P/keygen blind", rand);
+        let commitment = commit_point(&blind, &q1, &proof);
+        (P1KeyGen { x1, q1, proof, blind }, KeyGenMsg1 { commitment })
+    }
+
+    // Generating the Paillier key takes a while.
+    pub fn open(self, msg2: &KeyGenMsg2, rand: &[u8; 32]) -> Result<(P1KeyGenPdl, KeyGenMsg3), &'static str> {
+        if !msg2.proof.verify(&msg2.q2) {
+            return Err("P2's proof of knowledge is invalid");
+        }
+        let paillier = PaillierPrivateKey::generate(PAILLIER_BITS, rand);
+        let r = int_from_seed(b"ECDSA2P/c_key randomness", rand, paillier.public().n());
+        let c_key = paillier.public().encrypt(&self.x1, &r);
+        let range_proof = RangeProof::prove(paillier.public(), &c_key, &self.x1, &r, &key_share_bound(), rand);
+        let msg3 = KeyGenMsg3 {
+            q1: self.q1,
+            proof: self.proof,
+            blind: self.blind,
+            paillier: paillier.public().clone(),
+            paillier_proof: paillier.correct_key_proof(),
+            c_key,
+            range_proof,
+        };
+        let pubkey = PublicKey::from(&self.x1 * Point::from(msg2.q2.clone()));
+        Ok((P1KeyGenPdl { share: P1KeyShare { x1: self.x1, paillier, pubkey } }, msg3))
+    }
+}
+
+impl P1KeyGenPdl {
+    pub fn respond(self, msg4: &KeyGenMsg4, rand: &[u8; 32]) -> Result<(P1KeyGenPdlOpen, KeyGenMsg5), &'static str> {
+        if !self.share.paillier.public().is_valid_ciphertext(&msg4.c_prime) {
+            return Err("The PDL challenge isn't a valid ciphertext");
+        }
+        let alpha = self.share.paillier.decrypt(&msg4.c_prime);
+        let q_hat = PublicKey::from(get_context().mul_generator(&(Integer::from(&alpha % &get_context().order))));
+        let blind = blinding(b"ECDSA2P/pdl blind", rand);
+        let msg5 = KeyGenMsg5 { commitment: commit(&blind, &q_hat.compressed()) };
+        let state =
+            P1KeyGenPdlOpen { share: self.share, c_prime: msg4.c_prime.clone(), commitment: msg4.commitment, alpha, q_hat, blind };
+        Ok((state, msg5))
+    }
+}
+
+impl P1KeyGenPdlOpen {
+    // Only reveal alpha*G if c' really is Enc(a*x1 + b), otherwise P2 could learn something about x1.
+    pub fn finish(self, msg6: &KeyGenMsg6) -> Result<(P1KeyShare, KeyGenMsg7), &'static str> {
+        if commit(&msg6.blind, &pdl_commitment_data(&msg6.a, &msg6.b, &self.c_prime)) != self.commitment {
+            return Err("P2 opened the PDL commitment to different values");
+        }
+        if self.alpha != Integer::from(&msg6.a * &self.share.x1) + &msg6.b {
+            return Err("The PDL challenge isn't the claimed affine function of x1");
+        }
+        Ok((self.share, KeyGenMsg7 { q_hat: self.q_hat, blind: self.blind }))
+    }
+}
+
+fn pdl_commitment_data(a: &Integer, b: &Integer, c_prime: &Integer) -> Vec<u8> {
+    let b_bytes = b.to_digits::<u8>(Order::MsfBe);
+    let c_bytes = c_prime.to_digits::<u8>(Order::MsfBe);
+    [&int_to_bytes(a)[..], &(b_bytes.len() as u32).to_be_bytes(), &b_bytes, &c_bytes].concat()
+}
+
+impl P2KeyGen {
+    pub fn new(msg1: &KeyGenMsg1, rand: &[u8; 32]) -> (P2KeyGen, KeyGenMsg2) {
+        let (x2, q2, proof) = key_share(b"ECDSA2P/x2", rand, &get_context().order);
+        (P2KeyGen { x2, commitment: msg1.commitment }, KeyGenMsg2 { q2, proof })
+    }
+
+    pub fn verify_open(self, msg3: &KeyGenMsg3, rand: &[u8; 32]) -> Result<(P2KeyGenPdl, KeyGenMsg4), &'static str> {
+        let order = &get_context().order;
+        if commit_point(&msg3.blind, &msg3.q1, &msg3.proof) != self.commitment {
+            return Err("P1 opened its commitment to a different key");
+        }
+        if !msg3.proof.verify(&msg3.q1) {
+            return Err("P1's proof of knowledge is invalid");
+        }
+        // The PDL check only works if a*x1 + b < n.
+        if msg3.paillier.n().significant_bits() < PAILLIER_BITS || !msg3.paillier_proof.verify(&msg3.paillier) {
+            return Err("P1's Paillier key is invalid");
+        }
+        if !msg3.paillier.is_valid_ciphertext(&msg3.c_key) {
+            return Err("c_key isn't a valid ciphertext");
+        }
+        // Otherwise c_key could be Enc(x1 + j*q), which passes the PDL check but makes the plaintext
+        // in `P2Sign::finish` wrap around n, and whether signing works then leaks bits of x2.
+        if !msg3.range_proof.verify(&msg3.paillier, &msg3.c_key, &key_share_bound()) {
+            return Err("The range proof for c_key is invalid");
+        }
+
+        let a = int_from_seed(b"ECDSA2P/pdl a", rand, order);
+        let b = int_from_seed(b"ECDSA2P/pdl b", rand, &Integer::from(order.square_ref()));
+        let enc_b = msg3.paillier.encrypt_with_seed(&b, &blinding(b"ECDSA2P/pdl randomness", rand));
+        let c_prime = msg3.paillier.add(&msg3.paillier.mul(&msg3.c_key, &a), &enc_b);
+        let blind = blinding(b"ECDSA2P/pdl blind", rand);
+        let commitment = commit(&blind, &pdl_commitment_data(&a, &b, &c_prime));
+        let q_prime = PublicKey::from(&a * Point::from(msg3.q1.clone()) + get_context().mul_generator(&Integer::from(&b % order)));
+
+        let pubkey = PublicKey::from(&self.x2 * Point::from(msg3.q1.clone()));
+        let share = P2KeyShare { x2: self.x2, paillier: msg3.paillier.clone(), c_key: msg3.c_key.clone(), pubkey };
+        Ok((P2KeyGenPdl { share, q_prime, a, b, blind }, KeyGenMsg4 { c_prime, commitment }))
+    }
+}
+
+impl P2KeyGenPdl {
+    pub fn open(self, msg5: &KeyGenMsg5) -> (P2KeyGenPdlOpen, KeyGenMsg6) {
+        let msg6 = KeyGenMsg6 { a: self.a, b: self.b, blind: self.blind };
+        (P2KeyGenPdlOpen { share: self.share, q_prime: self.q_prime, commitment: msg5.commitment }, msg6)
+    }
+}
+
+impl P2KeyGenPdlOpen {
+    pub fn finish(self, msg7: &KeyGenMsg7) -> Result<P2KeyShare, &'static str> {
+        if commit(&msg7.blind, &msg7.q_hat.compressed()) != self.commitment || msg7.q_hat != self.q_prime {
+            return Err("c_key doesn't encrypt the discrete log of Q1");
+        }
+        Ok(self.share)
+    }
+}
+
+// P1 -> P2: commitment to R1 = k1*G and its proof.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignMsg1 {
+    commitment: [u8; 32],
+}
+
+// P2 -> P1: R2 = k2*G and its proof.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignMsg2 {
+    r2: PublicKey,
+    proof: DLogProof,
+}
+
+// P1 -> P2: opens R1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignMsg3 {
+    r1: PublicKey,
+    proof: DLogProof,
+    blind: [u8; 32],
+}
+
+// P2 -> P1: c3 = Enc(rho*n + k2^-1*m + k2^-1*r*x2*x1), where rho*n hides everything but the value mod n.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignMsg4 {
+    c3: Integer,
+}
+
+// P1 waiting for `SignMsg2`.
+pub struct P1Sign {
+    k1: Integer,
+    r1: PublicKey,
+    proof: DLogProof,
+    blind: [u8; 32],
+}
+
+// P1 waiting for `SignMsg4`.
+pub struct P1SignFinish {
+    k1: Integer,
+    r: Integer,
+}
+
+// P2 waiting for `SignMsg3`.
+pub struct P2Sign {
+    k2: Integer,
+    commitment: [u8; 32],
+}
+
+impl P1Sign {
+    pub fn new(rand: &[u8; 32]) -> (P1Sign, SignMsg1) {
+        let (k1, r1, proof) = key_share(b"ECDSA2P/k1", rand, &get_context().order);
+        let blind = blinding(b"ECDSA2P/sign blind", rand);
+        let commitment = commit_point(&blind, &r1, &proof);
+        (P1Sign { k1, r1, proof, blind }, SignMsg1 { commitment })
+    }
+
+    pub fn open(self, msg2: &SignMsg2) -> Result<(P1SignFinish, SignMsg3), &'static str> {
+        if !msg2.proof.verify(&msg2.r2) {
+            return Err("P2's proof of knowledge is invalid");
+        }
+        let r = nonce_x(&self.k1 * Point::from(msg2.r2.clone()))?;
+        Ok((P1SignFinish { k1: self.k1, r }, SignMsg3 { r1: self.r1, proof: self.proof, blind: self.blind }))
+    }
+}
+
+impl P1SignFinish {
+    // Decrypt P2's part, finish the signature and check it, so a misbehaving P2 is caught here.
+    pub fn finish(self, share: &P1KeyShare, msg4: &SignMsg4, msg_hash: &[u8; 32]) -> Result<Signature, &'static str> {
+        let order = &get_context().order;
+        if !share.paillier.public().is_valid_ciphertext(&msg4.c3) {
+            return Err("c3 isn't a valid ciphertext");
+        }
+        let s = share.paillier.decrypt(&msg4.c3) % order;
+        let s = (s * self.k1.invert(order).unwrap()) % order;
+        // Low s, like `PrivateKey::sign`.
+        let s = if s > Integer::from(order / 2) { order - s } else { s };
+        if s == 0 {
+            return Err("The signature is zero");
+        }
+        let sig = Signature::new(&int_to_bytes(&self.r), &int_to_bytes(&s));
+        if !share.pubkey.verify_ref(msg_hash, &sig, false) {
+            return Err("The signature is invalid, P2 misbehaved");
+        }
+        Ok(sig)
+    }
+}
+
+impl P2Sign {
+    pub fn new(msg1: &SignMsg1, rand: &[u8; 32]) -> (P2Sign, SignMsg2) {
+        let (k2, r2, proof) = key_share(b"ECDSA2P/k2", rand, &get_context().order);
+        (P2Sign { k2, commitment: msg1.commitment }, SignMsg2 { r2, proof })
+    }
+
+    pub fn finish(self, share: &P2KeyShare, msg3: &SignMsg3, msg_hash: &[u8; 32], rand: &[u8; 32]) -> Result<SignMsg4, &'static str> {
+        let order = &get_context().order;
+        if commit_point(&msg3.blind, &msg3.r1, &msg3.proof) != self.commitment {
+            return Err("P1 opened its commitment to a different nonce");
+        }
+        if !msg3.proof.verify(&msg3.r1) {
+            return Err("P1's proof of knowledge is invalid");
+        }
+        let r = nonce_x(&self.k2 * Point::from(msg3.r1.clone()))?;
+
+        let m = int_from_bytes(msg_hash) % order;
+        let k2_inv = self.k2.invert(order).unwrap();
+        let rho = int_from_seed(b"ECDSA2P/rho", rand, &Integer::from(order.square_ref()));
+        let c1 =
+            share.paillier.encrypt_with_seed(&(rho * order + (Integer::from(&k2_inv * &m) % order)), &blinding(b"ECDSA2P/c1", rand));
+        let v = (k2_inv * r * &share.x2) % order;
+        let c3 = share.paillier.add(&c1, &share.paillier.mul(&share.c_key, &v));
+        Ok(SignMsg4 { c3 })
+    }
+}
+
+// r = R.x mod n
+fn nonce_x(r: Point) -> Result<Integer, &'static str> {
+    if r.is_on_infinity() {
+        return Err("The nonce is the point at infinity");
+    }
+    let r = r.x.num % &get_context().order;
+    if r == 0 {
+        return Err("The nonce's x coordinate is zero");
+    }
+    Ok(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secp256k1::PrivateKey;
+
+    fn keygen(seed: u8) -> (P1KeyShare, P2KeyShare) {
+        let (p1, msg1) = P1KeyGen::new(&[seed; 32]);
+        let (p2, msg2) = P2KeyGen::new(&msg1, &[seed + 1; 32]);
+        let (p1, msg3) = p1.open(&msg2, &[seed + 2; 32]).unwrap();
+        let (p2, msg4) = p2.verify_open(&msg3, &[seed + 3; 32]).unwrap();
+        let (p1, msg5) = p1.respond(&msg4, &[seed + 4; 32]).unwrap();
+        let (p2, msg6) = p2.open(&msg5);
+        let (p1, msg7) = p1.finish(&msg6).unwrap();
+        (p1, p2.finish(&msg7).unwrap())
+    }
+
+    fn sign(p1_share: &P1KeyShare, p2_share: &P2KeyShare, msg_hash: &[u8; 32], seed: u8) -> Result<Signature, &'static str> {
+        let (p1, msg1) = P1Sign::new(&[seed; 32]);
+        let (p2, msg2) = P2Sign::new(&msg1, &[seed + 1; 32]);
+        let (p1, msg3) = p1.open(&msg2)?;
+        let msg4 = p2.finish(p2_share, &msg3, msg_hash, &[seed + 2; 32])?;
+        p1.finish(p1_share, &msg4, msg_hash)
+    }
+
+    #[test]
+    fn test_two_party_ecdsa() {
+        let (p1_share, p2_share) = keygen(1);
+        assert_eq!(p1_share.pubkey(), p2_share.pubkey());
+        let x = (Integer::from(&p1_share.x1 * &p2_share.x2)) % &get_context().order;
+        assert_eq!(&PrivateKey::new(x).generate_pubkey(), p1_share.pubkey());
+
+        for (seed, msg) in [&b"first"[..], b"second", b"third"].iter().enumerate() {
+            let msg_hash = tagged_hash(b"ECDSA2P/test", msg);
+            let sig = sign(&p1_share, &p2_share, &msg_hash, 10 * seed as u8 + 10).unwrap();
+            assert!(p1_share.pubkey().verify_ref(&msg_hash, &sig, false));
+            assert!(!p1_share.pubkey().verify_ref(&tagged_hash(b"ECDSA2P/test", b"other"), &sig, false));
+            // Low s.
+            assert!(sig.serialize()[32] < 0x80);
+        }
+    }
+
+    #[test]
+    fn test_misbehaving_parties() {
+        let (p1_share, p2_share) = keygen(40);
+        let msg_hash = [7u8; 32];
+
+        // P2 signs a different message.
+        let (p1, msg1) = P1Sign::new(&[50; 32]);
+        let (p2, msg2) = P2Sign::new(&msg1, &[51; 32]);
+        let (p1, msg3) = p1.open(&msg2).unwrap();
+        let msg4 = p2.finish(&p2_share, &msg3, &[8u8; 32], &[52; 32]).unwrap();
+        assert_eq!(p1.finish(&p1_share, &msg4, &msg_hash).err(), Some("The signature is invalid, P2 misbehaved"));
+
+        // P1 opens a different nonce than it committed to.
+        let (p1, msg1) = P1Sign::new(&[53; 32]);
+        let (p2, msg2) = P2Sign::new(&msg1, &[54; 32]);
+        let (_, mut msg3) = p1.open(&msg2).unwrap();
+        let (_, other) = P1Sign::new(&[55; 32]).0.open(&msg2).unwrap();
+        msg3.r1 = other.r1;
+        msg3.proof = other.proof;
+        assert!(p2.finish(&p2_share, &msg3, &msg_hash, &[56; 32]).is_err());
+
+        // P2 sends R2 without knowing its discrete log.
+        let (p1, msg1) = P1Sign::new(&[57; 32]);
+        let (_, mut msg2) = P2Sign::new(&msg1, &[58; 32]);
+        msg2.r2 = p1_share.pubkey().clone();
+        assert!(p1.open(&msg2).is_err());
+    }
+
+    // msg3 with c_key = Enc(x1) and a range proof for it, which is only valid if x1 is in range.
+    fn with_c_key(msg3: &KeyGenMsg3, x1: Integer) -> KeyGenMsg3 {
+        let r = Integer::from(0x1234_5678u32);
+        let c_key = msg3.paillier.encrypt(&x1, &r);
+        let range_proof = RangeProof::prove(&msg3.paillier, &c_key, &x1, &r, &key_share_bound(), &[63; 32]);
+        KeyGenMsg3 { c_key, range_proof, ..msg3.clone() }
+    }
+
+    #[test]
+    fn test_keygen_misbehavior() {
+        let (p1, msg1) = P1KeyGen::new(&[60; 32]);
+        let (p2, msg2) = P2KeyGen::new(&msg1, &[61; 32]);
+        let mut bad_msg2 = msg2.clone();
+        bad_msg2.q2 = PrivateKey::new(5).generate_pubkey();
+        assert!(P1KeyGen::new(&[60; 32]).0.open(&bad_msg2, &[62; 32]).is_err());
+
+        let (p1, msg3) = p1.open(&msg2, &[62; 32]).unwrap();
+        let x1 = p1.share.x1.clone();
+        // c_key encrypts x1 + 1 instead of x1. An honest P1 notices when a and b are opened,
+        // and if it reveals alpha*G anyway P2 notices.
+        let bad_msg3 = with_c_key(&msg3, Integer::from(&x1 + 1));
+        let (bad_p2, msg4) = P2KeyGen::new(&msg1, &[61; 32]).0.verify_open(&bad_msg3, &[64; 32]).unwrap();
+        let (p1_pdl, msg5) = p1.respond(&msg4, &[65; 32]).unwrap();
+        let (q_hat, blind) = (p1_pdl.q_hat.clone(), p1_pdl.blind);
+        let (bad_p2, msg6) = bad_p2.open(&msg5);
+        assert_eq!(p1_pdl.finish(&msg6).err(), Some("The PDL challenge isn't the claimed affine function of x1"));
+        assert!(bad_p2.finish(&KeyGenMsg7 { q_hat, blind }).is_err());
+
+        // c_key encrypts x1 + j*q, which would pass the PDL check.
+        let order = &get_context().order;
+        let bad_msg3 = with_c_key(&msg3, Integer::from(&x1 + order));
+        let fresh_p2 = P2KeyGen::new(&msg1, &[61; 32]).0;
+        assert_eq!(fresh_p2.verify_open(&bad_msg3, &[64; 32]).err(), Some("The range proof for c_key is invalid"));
+        // Or with the proof for the real c_key.
+        let bad_msg3 = KeyGenMsg3 { c_key: msg3.paillier.encrypt(&Integer::from(&x1 + order), &Integer::from(3)), ..msg3.clone() };
+        assert!(P2KeyGen::new(&msg1, &[61; 32]).0.verify_open(&bad_msg3, &[64; 32]).is_err());
+        let mut bad_msg3 = msg3.clone();
+        bad_msg3.range_proof.responses.pop();
+        assert!(P2KeyGen::new(&msg1, &[61; 32]).0.verify_open(&bad_msg3, &[64; 32]).is_err());
+
+        // A different key in the opening.
+        let mut wrong_open = msg3.clone();
+        wrong_open.q1 = msg2.q2.clone();
+        assert!(P2KeyGen::new(&msg1, &[61; 32]).0.verify_open(&wrong_open, &[64; 32]).is_err());
+        // A modulus that's too small for the PDL check.
+        let mut small = msg3.clone();
+        let small_key = PaillierPrivateKey::generate(1024, &[66; 32]);
+        small.paillier = small_key.public().clone();
+        small.paillier_proof = small_key.correct_key_proof();
+        assert!(P2KeyGen::new(&msg1, &[61; 32]).0.verify_open(&small, &[64; 32]).is_err());
+
+        // P2 opens the PDL commitment to other values than it used.
+        let (p1, _) = P1KeyGen::new(&[60; 32]).0.open(&msg2, &[62; 32]).unwrap();
+        let (p2, msg4) = p2.verify_open(&msg3, &[64; 32]).unwrap();
+        let (p1, msg5) = p1.respond(&msg4, &[65; 32]).unwrap();
+        let (_, mut msg6) = p2.open(&msg5);
+        msg6.b += 1;
+        assert!(p1.finish(&msg6).is_err());
+    }
+}
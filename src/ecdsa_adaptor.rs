@@ -0,0 +1,314 @@
+use crate::field::FieldElement;
+use crate::point::Point;
+use crate::secp256k1::{get_context, PrivateKey, PublicKey, Signature};
+use crate::utility::{int_from_bytes, int_to_bytes, tagged_hash};
+use bitcoin_hashes::{sha256, Hash};
+use rug::Integer;
+
+// ECDSA adaptor signatures, compatible with the libsecp256k1-zkp `ecdsa_adaptor` module.
+// The signer encrypts a signature under an encryption key Y = y*G: with a nonce k it publishes R = k*Y and
+// R' = k*G, a DLEQ proof that both have the same discrete log, and s' = k^-1 * (m + r*x) where r = R.x.
+// Decrypting with y gives the signature (r, s'/y), which has the nonce k*y. Anyone holding both the adaptor
+// signature and the decrypted signature recovers y.
+
+// The adaptor nonce and the DLEQ proof nonce use the same hardened nonce function with different tags.
+const ADAPTOR_NONCE_TAG: &[u8] = b"ECDSAadaptor/non";
+const DLEQ_TAG: &[u8] = b"DLEQ";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EcdsaAdaptorSignature {
+    // k*Y
+    r: PublicKey,
+    // k*G
+    r_prime: PublicKey,
+    s: Integer,
+    dleq_e: Integer,
+    dleq_s: Integer,
+}
+
+impl EcdsaAdaptorSignature {
+    // R (33 bytes) || R' (33 bytes) || s' (32 bytes) || DLEQ e (32 bytes) || DLEQ s (32 bytes)
+    pub fn serialize(&self) -> [u8; 162] {
+        let mut res = [0u8; 162];
+        res[..33].copy_from_slice(&self.r.compressed());
+        res[33..66].copy_from_slice(&self.r_prime.compressed());
+        res[66..98].copy_from_slice(&int_to_bytes(&self.s));
+        res[98..130].copy_from_slice(&int_to_bytes(&self.dleq_e));
+        res[130..].copy_from_slice(&int_to_bytes(&self.dleq_s));
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<EcdsaAdaptorSignature, &'static str> {
+        let order = &get_context().order;
+        if ser.len() != 162 {
+            return Err("An ECDSA adaptor signature should be 162 bytes");
+        }
+        let r = PublicKey::from_compressed(&ser[..33])?;
+        let r_prime = PublicKey::from_compressed(&ser[33..66])?;
+        let scalars: Vec<_> = ser[66..].chunks(32).map(int_from_bytes).collect();
+        if scalars.iter().any(|scalar| scalar >= order) {
+            return Err("The ECDSA adaptor signature overflows the curve order");
+        }
+        let (s, dleq_e, dleq_s) = (scalars[0].clone(), scalars[1].clone(), scalars[2].clone());
+        Ok(EcdsaAdaptorSignature { r, r_prime, s, dleq_e, dleq_s })
+    }
+
+    // Check that decrypting with the discrete log of `enckey` gives a valid signature of `msg_hash` under `pubkey`.
+    pub fn verify(&self, pubkey: &PublicKey, msg_hash: &[u8; 32], enckey: &PublicKey) -> bool {
+        let order = &get_context().order;
+        if !dleq_verify(&self.dleq_e, &self.dleq_s, enckey, &self.r_prime, &self.r) {
+            return false;
+        }
+        let r = self.sig_r();
+        if r == 0 || self.s == 0 {
+            return false;
+        }
+        // R' is the nonce point the signature would have with s', like in plain ECDSA verification.
+        let (z, r, s) =
+            (FieldElement::from_serialize(msg_hash, order), FieldElement::new(r, order), FieldElement::new(&self.s, order));
+        pubkey.verify_raw_point(z, r, s) == Point::from(self.r_prime.clone())
+    }
+
+    // Decrypt with the discrete log of the encryption key. The result is only valid if `verify` passed.
+    pub fn decrypt(&self, deckey: &PrivateKey) -> Result<Signature, &'static str> {
+        let order = &get_context().order;
+        let y_inv = deckey.scalar.clone().invert(order).map_err(|_| "The decryption key is zero")?;
+        let s = (y_inv * &self.s) % order;
+        // Low s, like `PrivateKey::sign`.
+        let s = if s > Integer::from(order / 2) { order - s } else { s };
+        if s == 0 {
+            return Err("The signature is zero");
+        }
+        Ok(Signature::new(&int_to_bytes(&self.sig_r()), &int_to_bytes(&s)))
+    }
+
+    // Recover the decryption key from the signature decrypted out of this adaptor signature.
+    pub fn recover(&self, sig: &Signature, enckey: &PublicKey) -> Result<PrivateKey, &'static str> {
+        let order = &get_context().order;
+        let sig = sig.serialize();
+        if int_from_bytes(&sig[..32]) != self.sig_r() {
+            return Err("The signature doesn't belong to the adaptor signature");
+        }
+        let s_inv = int_from_bytes(&sig[32..]).invert(order).map_err(|_| "The signature s is zero")?;
+        // The decrypted s might have been negated to make it low.
+        let y = (s_inv * &self.s) % order;
+        let candidate = PrivateKey::new(y.clone());
+        if candidate.generate_pubkey() == *enckey {
+            return Ok(candidate);
+        }
+        let candidate = PrivateKey::new(order - y);
+        if candidate.generate_pubkey() == *enckey {
+            return Ok(candidate);
+        }
+        Err("The recovered key doesn't match the encryption key")
+    }
+
+    // The r of the decrypted signature, R.x mod n.
+    fn sig_r(&self) -> Integer {
+        int_from_bytes(&self.r.x_only()) % &get_context().order
+    }
+}
+
+// Create an adaptor signature of `msg_hash` encrypted under `enckey`.
+// `aux_rand` is mixed into the nonces like the BIP340 auxiliary randomness.
+pub fn encrypt(
+    sk: &PrivateKey,
+    msg_hash: &[u8; 32],
+    enckey: &PublicKey,
+    aux_rand: &[u8; 32],
+) -> Result<EcdsaAdaptorSignature, &'static str> {
+    let secp = get_context();
+    let order = &secp.order;
+    if sk.scalar == 0 || sk.scalar >= *order {
+        return Err("The private key is not in the range [1, n-1]");
+    }
+    let k = nonce(ADAPTOR_NONCE_TAG, &sk.serialize(), &enckey.compressed(), msg_hash, aux_rand)?;
+    let r_prime = PublicKey::from(secp.mul_generator(&k));
+    let r = PublicKey::from(&k * Point::from(enckey.clone()));
+    let (dleq_e, dleq_s) = dleq_prove(&k, enckey, &r_prime, &r, aux_rand)?;
+
+    let sig_r = int_from_bytes(&r.x_only()) % order;
+    let m = int_from_bytes(msg_hash) % order;
+    let s = (k.invert(order).unwrap() * (m + sig_r * &sk.scalar)) % order;
+    if s == 0 {
+        return Err("The adaptor signature s is zero");
+    }
+    Ok(EcdsaAdaptorSignature { r, r_prime, s, dleq_e, dleq_s })
+}
+
+// The hardened nonce function of libsecp256k1-zkp: hash_tag((key xor hash_aux(aux)) || pk || msg).
+fn nonce(tag: &[u8], key: &[u8; 32], pk: &[u8; 33], msg: &[u8], aux_rand: &[u8; 32]) -> Result<Integer, &'static str> {
+    let mut masked = tagged_hash(b"ECDSAadaptor/aux", aux_rand);
+    masked.iter_mut().zip(key.iter()).for_each(|(byte, key)| *byte ^= key);
+    let k = int_from_bytes(&tagged_hash(tag, &[&masked[..], pk, msg].concat()));
+    if k == 0 || k >= get_context().order {
+        return Err("The nonce is out of range");
+    }
+    Ok(k)
+}
+
+// A proof that P1 = x*G and P2 = x*Y have the same discrete log x.
+fn dleq_prove(
+    x: &Integer,
+    gen2: &PublicKey,
+    p1: &PublicKey,
+    p2: &PublicKey,
+    aux_rand: &[u8; 32],
+) -> Result<(Integer, Integer), &'static str> {
+    let secp = get_context();
+    let order = &secp.order;
+    let points_hash = sha256::Hash::hash(&[&p1.compressed()[..], &p2.compressed()].concat());
+    let k = nonce(DLEQ_TAG, &int_to_bytes(x), &gen2.compressed(), &points_hash[..], aux_rand)?;
+    let r1 = PublicKey::from(secp.mul_generator(&k));
+    let r2 = PublicKey::from(&k * Point::from(gen2.clone()));
+    let e = dleq_challenge(gen2, &r1, &r2, p1, p2);
+    let s = (k + Integer::from(&e * x)) % order;
+    Ok((e, s))
+}
+
+fn dleq_verify(e: &Integer, s: &Integer, gen2: &PublicKey, p1: &PublicKey, p2: &PublicKey) -> bool {
+    let secp = get_context();
+    let minus_e = Integer::from(&secp.order - e);
+    // R1 = s*G - e*P1, R2 = s*Y - e*P2
+    let r1 = secp.mul_generator(s) + &minus_e * Point::from(p1.clone());
+    let r2 = s * Point::from(gen2.clone()) + &minus_e * Point::from(p2.clone());
+    if r1.is_on_infinity() || r2.is_on_infinity() {
+        return false;
+    }
+    dleq_challenge(gen2, &r1.into(), &r2.into(), p1, p2) == *e
+}
+
+fn dleq_challenge(gen2: &PublicKey, r1: &PublicKey, r2: &PublicKey, p1: &PublicKey, p2: &PublicKey) -> Integer {
+    let points: Vec<_> = [p1, gen2, p2, r1, r2].iter().flat_map(|p| p.compressed().to_vec()).collect();
+    int_from_bytes(&tagged_hash(DLEQ_TAG, &points)) % &get_context().order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utility::hex_to_bytes;
+
+    fn key(hex: &str) -> PublicKey {
+        PublicKey::from_compressed(&hex_to_bytes(hex)).unwrap()
+    }
+
+    fn msg(hex: &str) -> [u8; 32] {
+        let mut msg = [0u8; 32];
+        msg.copy_from_slice(&hex_to_bytes(hex));
+        msg
+    }
+
+    // The specification test vector from libsecp256k1-zkp `ecdsa_adaptor`.
+    const SPEC_ADAPTOR_SIG: &str = "03424d14a5471c048ab87b3b83f6085d125d5864249ae4297a57c84e74710bb6730223f325042fce535d040fee52ec13231bf709ccd84233c6944b90317e62528b2527dff9d659a96db4c99f9750168308633c1867b70f3a18fb0f4539a1aecedcd1fc0148fc22f36b6303083ece3f872b18e35d368b3958efe5fb081f7716736ccb598d269aa3084d57e1855e1ea9a45efc10463bbf32ae378029f5763ceb40173f";
+    const SPEC_MSG_HASH: &str = "8131e6f4b45754f2c90bd06688ceeabc0c45055460729928b4eecf11026a9e2d";
+    const SPEC_PUBKEY: &str = "035be5e9478209674a96e60f1f037f6176540fd001fa1d64694770c56a7709c42c";
+    const SPEC_ENCKEY: &str = "02c2662c97488b07b6e819124b8989849206334a4c2fbdf691f7b34d2b16e9c293";
+    const SPEC_DECKEY: &str = "0b2aba63b885a0f0e96fa0f303920c7fb7431ddfa94376ad94d969fbf4109dc8";
+
+    #[test]
+    fn test_spec_vector() {
+        let ser = hex_to_bytes(SPEC_ADAPTOR_SIG);
+        let adaptor_sig = EcdsaAdaptorSignature::parse(&ser).unwrap();
+        let msg_hash = msg(SPEC_MSG_HASH);
+        let pubkey = key(SPEC_PUBKEY);
+        let enckey = key(SPEC_ENCKEY);
+        let deckey = PrivateKey::from_serialized(&hex_to_bytes(SPEC_DECKEY));
+        let expected = hex_to_bytes("424d14a5471c048ab87b3b83f6085d125d5864249ae4297a57c84e74710bb67329e80e0ee60e57af3e625bbae1672b1ecaa58effe613426b024fa1621d903394");
+
+        assert_eq!(adaptor_sig.serialize()[..], ser[..]);
+        assert!(adaptor_sig.verify(&pubkey, &msg_hash, &enckey));
+        let sig = adaptor_sig.decrypt(&deckey).unwrap();
+        assert_eq!(sig.serialize()[..], expected[..]);
+        assert!(pubkey.verify(&msg_hash, Signature::parse_slice(&expected), false));
+        assert_eq!(adaptor_sig.recover(&sig, &enckey).unwrap().serialize(), deckey.serialize());
+
+        // Breaking the DLEQ proof or the message fails verification.
+        let mut bad_proof = ser.clone();
+        bad_proof[161] ^= 1;
+        assert!(!EcdsaAdaptorSignature::parse(&bad_proof).unwrap().verify(&pubkey, &msg_hash, &enckey));
+        let mut bad_msg = msg_hash;
+        bad_msg[0] ^= 1;
+        assert!(!adaptor_sig.verify(&pubkey, &bad_msg, &enckey));
+    }
+
+    // The spec vector with each field made invalid in turn.
+    #[test]
+    fn test_spec_vector_rejections() {
+        let ser = hex_to_bytes(SPEC_ADAPTOR_SIG);
+        let msg_hash = msg(SPEC_MSG_HASH);
+        let pubkey = key(SPEC_PUBKEY);
+        let enckey = key(SPEC_ENCKEY);
+        let order = hex_to_bytes("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+        let replace = |start: usize, bytes: &[u8]| [&ser[..start], bytes, &ser[start + bytes.len()..]].concat();
+
+        // There's no point with x = 0, and 04 isn't a compressed point prefix.
+        assert!(EcdsaAdaptorSignature::parse(&replace(0, &hex_to_bytes(&format!("02{:064}", 0)))).is_err());
+        assert!(EcdsaAdaptorSignature::parse(&replace(33, &[4])).is_err());
+        // s', the DLEQ challenge and the DLEQ response can't overflow the order.
+        for start in [66, 98, 130].iter() {
+            assert_eq!(
+                EcdsaAdaptorSignature::parse(&replace(*start, &order)).err(),
+                Some("The ECDSA adaptor signature overflows the curve order")
+            );
+        }
+        // A zero s' parses but doesn't verify.
+        let zero_s = EcdsaAdaptorSignature::parse(&replace(66, &[0; 32])).unwrap();
+        assert!(!zero_s.verify(&pubkey, &msg_hash, &enckey));
+        // Swapping R and R' breaks the DLEQ proof.
+        let swapped = [&ser[33..66], &ser[..33], &ser[66..]].concat();
+        assert!(!EcdsaAdaptorSignature::parse(&swapped).unwrap().verify(&pubkey, &msg_hash, &enckey));
+
+        // Recovery needs the encryption key the adaptor signature was made for.
+        let adaptor_sig = EcdsaAdaptorSignature::parse(&ser).unwrap();
+        let deckey = PrivateKey::from_serialized(&hex_to_bytes(SPEC_DECKEY));
+        let sig = adaptor_sig.decrypt(&deckey).unwrap();
+        assert_eq!(adaptor_sig.recover(&sig, &pubkey).err(), Some("The recovered key doesn't match the encryption key"));
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_recover() {
+        for i in 1..=4u8 {
+            let sk = PrivateKey::from_serialized(&[i; 32]);
+            let deckey = PrivateKey::from_serialized(&[i + 0x40; 32]);
+            let (pubkey, enckey) = (sk.generate_pubkey(), deckey.generate_pubkey());
+            let msg_hash = tagged_hash(b"ECDSA adaptor test", &[i]);
+
+            let adaptor_sig = encrypt(&sk, &msg_hash, &enckey, &[i; 32]).unwrap();
+            assert!(adaptor_sig.verify(&pubkey, &msg_hash, &enckey));
+            assert_eq!(EcdsaAdaptorSignature::parse(&adaptor_sig.serialize()).unwrap(), adaptor_sig);
+            assert_ne!(encrypt(&sk, &msg_hash, &enckey, &[0; 32]).unwrap(), adaptor_sig);
+
+            let sig = adaptor_sig.decrypt(&deckey).unwrap();
+            assert!(pubkey.verify_ref(&msg_hash, &sig, false));
+            assert_eq!(adaptor_sig.recover(&sig, &enckey).unwrap().serialize(), deckey.serialize());
+        }
+    }
+
+    #[test]
+    fn test_invalid_adaptor_signature() {
+        let sk = PrivateKey::from_serialized(&[0x11; 32]);
+        let deckey = PrivateKey::from_serialized(&[0x22; 32]);
+        let other = PrivateKey::from_serialized(&[0x33; 32]);
+        let (pubkey, enckey) = (sk.generate_pubkey(), deckey.generate_pubkey());
+        let msg_hash = [7u8; 32];
+        let adaptor_sig = encrypt(&sk, &msg_hash, &enckey, &[0; 32]).unwrap();
+
+        assert!(!adaptor_sig.verify(&other.generate_pubkey(), &msg_hash, &enckey));
+        assert!(!adaptor_sig.verify(&pubkey, &msg_hash, &other.generate_pubkey()));
+        let mut tampered = adaptor_sig.clone();
+        tampered.s += 1;
+        assert!(!tampered.verify(&pubkey, &msg_hash, &enckey));
+
+        // The wrong decryption key gives an invalid signature, and nothing can be recovered from it.
+        let sig = adaptor_sig.decrypt(&other).unwrap();
+        assert!(!pubkey.verify_ref(&msg_hash, &sig, false));
+        assert!(adaptor_sig.recover(&sig, &enckey).is_err());
+        let unrelated = encrypt(&sk, &[8u8; 32], &enckey, &[0; 32]).unwrap().decrypt(&deckey).unwrap();
+        assert_eq!(adaptor_sig.recover(&unrelated, &enckey).err(), Some("The signature doesn't belong to the adaptor signature"));
+
+        let ser = adaptor_sig.serialize();
+        assert!(EcdsaAdaptorSignature::parse(&ser[1..]).is_err());
+        assert!(EcdsaAdaptorSignature::parse(&[&ser[..66], &[0xff; 32][..], &ser[98..]].concat()).is_err());
+        assert!(encrypt(&PrivateKey::new(0), &msg_hash, &enckey, &[0; 32]).is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use crate::field::FieldElement;
+use crate::point::Point;
+use crate::secp256k1::{fe_from_hex, get_context, PrivateKey, PublicKey};
+use crate::utility::tagged_hash;
+
+// ElligatorSwift (BIP324): a public key encoded as 64 bytes (u, t) that look uniformly random, so a key exchange
+// can't be told apart from random data on the wire. Decoding maps every 64 bytes to a point:
+// the x-coordinate comes from the SwiftEC map of (u, t) and the y has the parity of t.
+// Encoding picks a random u and inverts the map for t, which works for about a quarter of the u's.
+
+// c1 = (sqrt(-3)-1)/2 and c2 = (-sqrt(-3)-1)/2
+const C1: &str = "851695d49a83f8ef919bb86153cbcb16630fb68aed0a766a3ec693d68e6afa40";
+const C2: &str = "7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee";
+
+// Encode the public key. `rand` must be fresh randomness, otherwise the encoding can be linked to the key.
+pub fn ellswift_encode(pubkey: &PublicKey, rand: &[u8; 32]) -> [u8; 64] {
+    // The randomness is hash(pubkey || 0^31 || rand || counter).
+    let prefix = [&pubkey.compressed()[..], &[0u8; 31], rand].concat();
+    encode_point(&Point::from(pubkey.clone()), b"secp256k1_ellswift_encode", &prefix)
+}
+
+// The encoded public key of `sk`. The randomness comes from the private key, mixed with `aux_rand` if given.
+pub fn ellswift_create(sk: &PrivateKey, aux_rand: Option<&[u8; 32]>) -> Result<[u8; 64], &'static str> {
+    let secp = get_context();
+    if sk.scalar == 0 || sk.scalar >= secp.order {
+        return Err("The private key is not in the range [1, n-1]");
+    }
+    // The randomness is hash(sk || 0^32 [|| aux_rand] || counter).
+    let mut prefix = [&sk.serialize()[..], &[0u8; 32]].concat();
+    if let Some(aux_rand) = aux_rand {
+        prefix.extend_from_slice(aux_rand);
+    }
+    Ok(encode_point(&secp.mul_generator(&sk.scalar), b"secp256k1_ellswift_create", &prefix))
+}
+
+// Every 64 bytes decode to a valid public key.
+pub fn ellswift_decode(ell: &[u8; 64]) -> PublicKey {
+    let secp = get_context();
+    let u = secp.get_fe(&ell[..32]).mod_num().clone();
+    let t = secp.get_fe(&ell[32..]).mod_num().clone();
+    let x = xswiftec(&u, &t);
+    let mut y = secp.generator().group.get_y(&x);
+    if y.is_even() != t.is_even() {
+        y.reflect();
+    }
+    PublicKey::from(Point { x, y, group: secp.generator().group })
+}
+
+// x-only ECDH on the encoded keys with the BIP324 hash: hash_bip324_ellswift_xonly_ecdh(ell_a || ell_b || x),
+// where ell_a is the initiator's encoding and ell_b the responder's.
+pub fn ellswift_xdh(ell_a: &[u8; 64], ell_b: &[u8; 64], sk: &PrivateKey, initiating: bool) -> Result<[u8; 32], &'static str> {
+    let theirs = if initiating { ell_b } else { ell_a };
+    let x = xdh_shared_x(theirs, sk)?;
+    Ok(tagged_hash(b"bip324_ellswift_xonly_ecdh", &[&ell_a[..], ell_b, &x].concat()))
+}
+
+// The x-coordinate of sk * their key. It doesn't depend on the y of their key, so only the x is decoded.
+fn xdh_shared_x(theirs: &[u8; 64], sk: &PrivateKey) -> Result<[u8; 32], &'static str> {
+    let secp = get_context();
+    let u = secp.get_fe(&theirs[..32]).mod_num().clone();
+    let t = secp.get_fe(&theirs[32..]).mod_num().clone();
+    sk.ecdh_from_x(&xswiftec(&u, &t).serialize_num())
+}
+
+// Try random u's until one of the branches of the inverse map gives a t for the x-coordinate,
+// then pick the sign of t that gives the y of the point.
+fn encode_point(point: &Point, tag: &[u8], prefix: &[u8]) -> [u8; 64] {
+    let secp = get_context();
+    let prng = |cnt: u32| tagged_hash(tag, &[prefix, &cnt.to_le_bytes()].concat());
+    let mut cnt = 0;
+    // A pool of 64 4-bit branch values, the top bit of each is unused.
+    let mut branch_hash = [0u8; 32];
+    let mut branches_left = 0;
+    loop {
+        if branches_left == 0 {
+            branch_hash = prng(cnt);
+            cnt += 1;
+            branches_left = 64;
+        }
+        branches_left -= 1;
+        let branch = (branch_hash[branches_left >> 1] >> ((branches_left & 1) << 2)) & 7;
+        let u_bytes = prng(cnt);
+        cnt += 1;
+        let u = secp.get_fe(&u_bytes).mod_num().clone();
+        if let Some(mut t) = xswiftec_inv(&point.x, &u, branch) {
+            if t.is_even() != point.y.is_even() {
+                t.reflect();
+            }
+            let mut res = [0u8; 64];
+            res[..32].copy_from_slice(&u_bytes);
+            res[32..].copy_from_slice(&t.serialize_num());
+            return res;
+        }
+    }
+}
+
+// The SwiftEC map of (u, t) to an x-coordinate on the curve:
+// the first valid one of x3 = u - (g+s)^2/(3*s*u^2), x2 = u*(c1*s + c2*g)/(g+s) and x1 = -(x2 + u),
+// with s = t^2 and g = u^3 + 7.
+fn xswiftec(u: &FieldElement, t: &FieldElement) -> FieldElement {
+    let modulo = &u.modulo;
+    let one = FieldElement::new(1, modulo);
+    let u = if u.is_zero() { one.clone() } else { u.clone() };
+    let mut s = if t.is_zero() { one } else { t.clone().pow_u(2) };
+    let g = u.clone().pow_u(3) + 7u8;
+    if (g.clone() + &s).is_zero() {
+        s = s * 4;
+    }
+    let g_plus_s = g.clone() + &s;
+
+    let x3 = u.clone() - g_plus_s.clone().pow_u(2) / (3 * s.clone() * u.clone().pow_u(2));
+    if is_valid_x(&x3) {
+        return x3;
+    }
+    let x2 = u.clone() * (fe_from_hex(C1) * s + fe_from_hex(C2) * g) / g_plus_s;
+    if is_valid_x(&x2) {
+        return x2;
+    }
+    let mut x1 = x2 + u;
+    x1.reflect();
+    x1
+}
+
+// One of the up to 8 t's for which (u, t) decodes to `x`. Branches 0, 1, 4 and 5 invert the x1 and x2 formulas,
+// 2, 3, 6 and 7 the x3 one, and the branches with 4 set take the other square root.
+fn xswiftec_inv(x: &FieldElement, u: &FieldElement, branch: u8) -> Option<FieldElement> {
+    let modulo = &u.modulo;
+    let g = u.clone().pow_u(3) + 7u8;
+    let (s, v) = if branch & 2 == 0 {
+        // If -x-u is a valid x, the decoding would take the x3 formula instead.
+        let mut minus_x_u = x.clone() + u;
+        minus_x_u.reflect();
+        if is_valid_x(&minus_x_u) {
+            return None;
+        }
+        // s = -(u^3 + 7)/(u^2 + u*x + x^2)
+        let den = u.clone().pow_u(2) + u.clone() * x + x.clone().pow_u(2);
+        let mut s = g / den;
+        s.reflect();
+        if !s.is_square() {
+            return None;
+        }
+        (s, x.clone())
+    } else {
+        let s = x.clone() - u;
+        if !s.is_square() {
+            return None;
+        }
+        // r = sqrt(-s*(4*(u^3 + 7) + 3*u^2*s))
+        let mut q = s.clone() * (g * 4u8 + u.clone().pow_u(2) * 3u8 * &s);
+        q.reflect();
+        if !q.is_square() {
+            return None;
+        }
+        let mut r = q;
+        r.sqrt();
+        if (branch & 1 == 1 && r.is_zero()) || s.is_zero() {
+            return None;
+        }
+        // v = (r/s - u)/2
+        let v = (r / &s - u) / FieldElement::new(2, modulo);
+        (s, v)
+    };
+    let mut w = s;
+    w.sqrt();
+    if branch & 5 == 0 || branch & 5 == 5 {
+        w.reflect();
+    }
+    // c3 = -c1 and c4 = c1 + 1
+    let c = if branch & 1 == 1 {
+        fe_from_hex(C1) + 1
+    } else {
+        let mut c3 = fe_from_hex(C1);
+        c3.reflect();
+        c3
+    };
+    Some(w * (c * u + v))
+}
+
+// x is on the curve if x^3 + 7 is a square.
+fn is_valid_x(x: &FieldElement) -> bool {
+    (x.clone().pow_u(3) + &get_context().generator().group.b).is_square()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_vectors::{ELLSWIFT_DECODE_VECTORS, ELLSWIFT_INV_VECTORS, ELLSWIFT_XDH_VECTORS};
+    use crate::utility::hex_to_bytes;
+    use std::convert::TryInto;
+
+    fn ell(hex: &str) -> [u8; 64] {
+        hex_to_bytes(hex)[..].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_xswiftec_inv_vectors() {
+        for (bitmap, u, x, encs) in ELLSWIFT_INV_VECTORS.iter() {
+            let (u, x) = (fe_from_hex(u), fe_from_hex(x));
+            for (branch, enc) in encs.iter().enumerate() {
+                let t = xswiftec_inv(&x, &u, branch as u8);
+                assert_eq!(t.is_some(), bitmap >> branch & 1 == 1);
+                if let Some(t) = t {
+                    assert_eq!(t, fe_from_hex(enc));
+                    assert_eq!(xswiftec(&u, &t), x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_vectors() {
+        for (enc, x, odd_y) in ELLSWIFT_DECODE_VECTORS.iter() {
+            let pubkey = ellswift_decode(&ell(enc));
+            assert_eq!(pubkey.x_only()[..], hex_to_bytes(x)[..]);
+            assert_eq!(pubkey.has_even_y(), !odd_y);
+        }
+    }
+
+    #[test]
+    fn test_xdh_vectors() {
+        for (sk, ours, theirs, initiating, shared) in ELLSWIFT_XDH_VECTORS.iter() {
+            let sk = PrivateKey::from_serialized(&hex_to_bytes(sk));
+            let (ours, theirs) = (ell(ours), ell(theirs));
+            let (ell_a, ell_b) = if *initiating { (&ours, &theirs) } else { (&theirs, &ours) };
+            assert_eq!(ellswift_xdh(ell_a, ell_b, &sk, *initiating).unwrap()[..], hex_to_bytes(shared)[..]);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for i in 1..=8u8 {
+            let sk = PrivateKey::from_serialized(&[i; 32]);
+            let pubkey = sk.generate_pubkey();
+            let encoded = ellswift_encode(&pubkey, &[i + 100; 32]);
+            assert_eq!(ellswift_decode(&encoded), pubkey);
+            assert_ne!(ellswift_encode(&pubkey, &[i + 101; 32]), encoded);
+
+            let created = ellswift_create(&sk, None).unwrap();
+            assert_eq!(ellswift_decode(&created), pubkey);
+            assert_eq!(ellswift_create(&sk, None).unwrap(), created);
+            assert_eq!(ellswift_decode(&ellswift_create(&sk, Some(&[i; 32])).unwrap()), pubkey);
+        }
+        assert!(ellswift_create(&PrivateKey::new(0), None).is_err());
+    }
+
+    #[test]
+    fn test_xdh() {
+        let (sk_a, sk_b) = (PrivateKey::from_serialized(&[1; 32]), PrivateKey::from_serialized(&[2; 32]));
+        let ell_a = ellswift_create(&sk_a, Some(&[3; 32])).unwrap();
+        let ell_b = ellswift_create(&sk_b, Some(&[4; 32])).unwrap();
+        let shared = ellswift_xdh(&ell_a, &ell_b, &sk_a, true).unwrap();
+        assert_eq!(ellswift_xdh(&ell_a, &ell_b, &sk_b, false).unwrap(), shared);
+        // The shared secret depends on the roles and on the encodings, not just on the keys.
+        assert_ne!(ellswift_xdh(&ell_a, &ell_b, &sk_a, false).unwrap(), shared);
+        let other_b = ellswift_create(&sk_b, Some(&[5; 32])).unwrap();
+        assert_ne!(ellswift_xdh(&ell_a, &other_b, &sk_a, true).unwrap(), shared);
+        assert!(ellswift_xdh(&ell_a, &ell_b, &PrivateKey::new(0), true).is_err());
+    }
+}
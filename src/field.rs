@@ -1,3 +1,4 @@
+use crate::jacobi::{jacobi_symbol, Jacobi};
 use rug::{integer::Order, ops::NegAssign, Integer};
 use std::{fmt, ops::*};
 
@@ -97,6 +98,11 @@ impl FieldElement {
     pub fn is_even(&self) -> bool {
         self.num.is_even()
     }
+
+    // Zero counts as a square, the modulo has to be an odd prime.
+    pub fn is_square(&self) -> bool {
+        jacobi_symbol(self.num.clone(), self.modulo.clone()) != Jacobi::MinusOne
+    }
 }
 
 #[inline(always)]
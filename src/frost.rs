@@ -0,0 +1,784 @@
+use crate::point::Point;
+use crate::secp256k1::{bip340_challenge, get_context, PrivateKey, PublicKey, SchnorrSignature};
+use crate::shamir::{commit_polynomial, eval_commitment, eval_polynomial, lagrange_coefficient};
+use crate::utility::{int_from_bytes, int_to_bytes, parse_scalar, scalar_from_seed, tagged_hash, zeroize};
+use rug::Integer;
+
+// FROST threshold Schnorr signatures (Komlo & Goldberg), the final signatures are plain BIP340 signatures.
+// Participants are identified by their index in 1..=n, which is also the point their share is evaluated at.
+// Keys come from a trusted dealer (`trusted_dealer_keygen`) or from a Pedersen DKG (`dkg_part1` to `dkg_part3`).
+// Signing takes two rounds: the signers send `SigningCommitments` from `commit`, the coordinator answers with a
+// `SigningPackage`, the signers reply with a `SignatureShare` from `sign` and the coordinator calls `aggregate`.
+// All the messages have a byte serialization, so the participants can run as separate processes.
+
+// A participant's share f(index) of the group private key.
+#[derive(Clone)]
+pub struct SecretShare {
+    index: u32,
+    value: Integer,
+}
+
+impl SecretShare {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    // Feldman verification: f(index)*G == C_0 + index*C_1 + ... + index^(t-1)*C_(t-1).
+    pub fn verify(&self, commitment: &[PublicKey]) -> bool {
+        get_context().mul_generator(&self.value) == eval_commitment(commitment, self.index)
+    }
+
+    // index (4 bytes big endian) || share
+    pub fn serialize(&self) -> [u8; 36] {
+        let mut res = [0u8; 36];
+        res[..4].copy_from_slice(&self.index.to_be_bytes());
+        res[4..].copy_from_slice(&int_to_bytes(&self.value));
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<SecretShare, &'static str> {
+        if ser.len() != 36 {
+            return Err("A secret share should be 36 bytes");
+        }
+        let index = parse_index(&ser[..4])?;
+        Ok(SecretShare { index, value: parse_scalar(&ser[4..])? })
+    }
+}
+
+// The Feldman commitment to the group polynomial. C_0 is the group key, and the verifying share
+// (public key) of every participant can be derived from it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicKeyPackage {
+    commitment: Vec<PublicKey>,
+}
+
+impl PublicKeyPackage {
+    pub fn group_pubkey(&self) -> &PublicKey {
+        &self.commitment[0]
+    }
+
+    // The BIP340 key the final signatures verify under.
+    pub fn x_only_group_pubkey(&self) -> [u8; 32] {
+        self.commitment[0].x_only()
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.commitment.len()
+    }
+
+    pub fn verifying_share(&self, index: u32) -> PublicKey {
+        eval_commitment(&self.commitment, index).into()
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        self.commitment.iter().flat_map(|c| c.compressed().to_vec()).collect()
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<PublicKeyPackage, &'static str> {
+        Ok(PublicKeyPackage { commitment: parse_commitment(ser)? })
+    }
+}
+
+// Everything a signer needs: its verified secret share and the public package.
+pub struct KeyPackage {
+    share: SecretShare,
+    public: PublicKeyPackage,
+}
+
+impl KeyPackage {
+    pub fn new(share: SecretShare, public: PublicKeyPackage) -> Result<KeyPackage, &'static str> {
+        if !share.verify(&public.commitment) {
+            return Err("The secret share doesn't match the commitment");
+        }
+        Ok(KeyPackage { share, public })
+    }
+
+    pub fn index(&self) -> u32 {
+        self.share.index
+    }
+
+    pub fn public(&self) -> &PublicKeyPackage {
+        &self.public
+    }
+}
+
+// Split `sk` into `n` shares, any `threshold` of them can sign. The dealer learns every share,
+// so it has to be trusted (or be the only owner of `sk` to begin with).
+pub fn trusted_dealer_keygen(
+    sk: &PrivateKey,
+    threshold: usize,
+    n: u32,
+    rand: &[u8; 32],
+) -> Result<(Vec<SecretShare>, PublicKeyPackage), &'static str> {
+    check_threshold(threshold, n)?;
+    if sk.scalar == 0 || sk.scalar >= get_context().order {
+        return Err("The private key is not in the range [1, n-1]");
+    }
+    let mut coefficients = vec![sk.scalar.clone()];
+    coefficients.extend(derive_scalars(b"FROST/coefficient", &[&rand[..], &sk.serialize()].concat(), 1..threshold));
+    let shares = (1..=n).map(|index| SecretShare { index, value: eval_polynomial(&coefficients, index) }).collect();
+    Ok((shares, PublicKeyPackage { commitment: commit_polynomial(&coefficients) }))
+}
+
+// A participant's secret state after the first DKG round.
+pub struct DkgSecretPackage {
+    index: u32,
+    n: u32,
+    coefficients: Vec<Integer>,
+    commitment: Vec<PublicKey>,
+}
+
+// Broadcast to every other participant in the first DKG round.
+// The proof of knowledge of the constant term prevents rogue key attacks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DkgRound1Package {
+    index: u32,
+    commitment: Vec<PublicKey>,
+    proof_r: PublicKey,
+    proof_z: Integer,
+}
+
+impl DkgRound1Package {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    // index (4 bytes big endian) || R || z || C_0 || ... || C_(t-1)
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut res = self.index.to_be_bytes().to_vec();
+        res.extend_from_slice(&self.proof_r.compressed());
+        res.extend_from_slice(&int_to_bytes(&self.proof_z));
+        self.commitment.iter().for_each(|c| res.extend_from_slice(&c.compressed()));
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<DkgRound1Package, &'static str> {
+        if ser.len() < 102 {
+            return Err("A DKG round 1 package should be at least 102 bytes");
+        }
+        Ok(DkgRound1Package {
+            index: parse_index(&ser[..4])?,
+            proof_r: PublicKey::from_compressed(&ser[4..37])?,
+            proof_z: parse_scalar(&ser[37..69])?,
+            commitment: parse_commitment(&ser[69..])?,
+        })
+    }
+
+    fn verify_proof(&self) -> bool {
+        let c = dkg_challenge(self.index, &self.commitment[0], &self.proof_r);
+        get_context().mul_generator(&self.proof_z) == Point::from(self.proof_r.clone()) + c * Point::from(self.commitment[0].clone())
+    }
+}
+
+// A participant's secret state after the second DKG round.
+pub struct DkgRound2Secret {
+    index: u32,
+    own_share: Integer,
+    commitment: Vec<PublicKey>,
+    round1: Vec<DkgRound1Package>,
+}
+
+// Sent to `receiver` in the second DKG round. It contains a secret share, so it must only be sent over
+// an encrypted and authenticated channel.
+#[derive(Clone)]
+pub struct DkgRound2Package {
+    sender: u32,
+    receiver: u32,
+    share: Integer,
+}
+
+impl DkgRound2Package {
+    pub fn receiver(&self) -> u32 {
+        self.receiver
+    }
+
+    // sender (4 bytes big endian) || receiver (4 bytes big endian) || share
+    pub fn serialize(&self) -> [u8; 40] {
+        let mut res = [0u8; 40];
+        res[..4].copy_from_slice(&self.sender.to_be_bytes());
+        res[4..8].copy_from_slice(&self.receiver.to_be_bytes());
+        res[8..].copy_from_slice(&int_to_bytes(&self.share));
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<DkgRound2Package, &'static str> {
+        if ser.len() != 40 {
+            return Err("A DKG round 2 package should be 40 bytes");
+        }
+        Ok(DkgRound2Package { sender: parse_index(&ser[..4])?, receiver: parse_index(&ser[4..8])?, share: parse_scalar(&ser[8..])? })
+    }
+}
+
+// First DKG round: pick a random polynomial, commit to it and prove knowledge of its constant term.
+// `rand` must be fresh randomness.
+pub fn dkg_part1(index: u32, threshold: usize, n: u32, rand: &[u8; 32]) -> Result<(DkgSecretPackage, DkgRound1Package), &'static str> {
+    check_threshold(threshold, n)?;
+    if index == 0 || index > n {
+        return Err("The participant index must be in the range [1, n]");
+    }
+    let seed = [&rand[..], &index.to_be_bytes()].concat();
+    let coefficients: Vec<_> = derive_scalars(b"FROST/coefficient", &seed, 0..threshold).collect();
+    let commitment = commit_polynomial(&coefficients);
+
+    let k = derive_scalars(b"FROST/dkg nonce", &seed, 0..1).next().unwrap();
+    let proof_r = PublicKey::from(get_context().mul_generator(&k));
+    let c = dkg_challenge(index, &commitment[0], &proof_r);
+    let proof_z = (k + c * &coefficients[0]) % &get_context().order;
+
+    let package = DkgRound1Package { index, commitment: commitment.clone(), proof_r, proof_z };
+    Ok((DkgSecretPackage { index, n, coefficients, commitment }, package))
+}
+
+// Second DKG round: check the round 1 packages of all the other participants and compute their shares.
+pub fn dkg_part2(
+    secret: DkgSecretPackage,
+    round1: &[DkgRound1Package],
+) -> Result<(DkgRound2Secret, Vec<DkgRound2Package>), &'static str> {
+    let mut indices: Vec<_> = round1.iter().map(|package| package.index).chain(Some(secret.index)).collect();
+    indices.sort_unstable();
+    if indices != (1..=secret.n).collect::<Vec<_>>() {
+        return Err("Expected exactly one round 1 package from every other participant");
+    }
+    for package in round1 {
+        if package.commitment.len() != secret.commitment.len() {
+            return Err("A round 1 package has a commitment with the wrong threshold");
+        }
+        if !package.verify_proof() {
+            return Err("A round 1 package has an invalid proof of knowledge");
+        }
+    }
+
+    let shares = round1
+        .iter()
+        .map(|package| DkgRound2Package {
+            sender: secret.index,
+            receiver: package.index,
+            share: eval_polynomial(&secret.coefficients, package.index),
+        })
+        .collect();
+    let own_share = eval_polynomial(&secret.coefficients, secret.index);
+    Ok((DkgRound2Secret { index: secret.index, own_share, commitment: secret.commitment, round1: round1.to_vec() }, shares))
+}
+
+// Last DKG step: verify the shares received from every other participant and combine them into the key package.
+pub fn dkg_part3(secret: DkgRound2Secret, round2: &[DkgRound2Package]) -> Result<KeyPackage, &'static str> {
+    let order = &get_context().order;
+    if round2.len() != secret.round1.len() {
+        return Err("Expected exactly one round 2 package from every other participant");
+    }
+    let mut value = secret.own_share.clone();
+    let mut commitment: Vec<_> = secret.commitment.iter().map(|c| Point::from(c.clone())).collect();
+    for package in &secret.round1 {
+        let mut received = round2.iter().filter(|share| share.sender == package.index);
+        let share = match (received.next(), received.next()) {
+            (Some(share), None) => share,
+            _ => return Err("Expected exactly one round 2 package from every other participant"),
+        };
+        if share.receiver != secret.index {
+            return Err("A round 2 package is addressed to another participant");
+        }
+        if !(SecretShare { index: secret.index, value: share.share.clone() }).verify(&package.commitment) {
+            return Err("A round 2 share doesn't match the sender's commitment");
+        }
+        value = (value + &share.share) % order;
+        commitment.iter_mut().zip(package.commitment.iter()).for_each(|(sum, c)| *sum = sum.clone() + Point::from(c.clone()));
+    }
+    if commitment.iter().any(Point::is_on_infinity) {
+        return Err("The group commitment contains the point at infinity");
+    }
+    let public = PublicKeyPackage { commitment: commitment.into_iter().map(PublicKey::from).collect() };
+    KeyPackage::new(SecretShare { index: secret.index, value }, public)
+}
+
+// The secret nonces d || e of one signing session, consumed by `sign` and zeroed when dropped.
+pub struct SigningNonces {
+    index: u32,
+    bytes: [u8; 64],
+}
+
+impl Drop for SigningNonces {
+    fn drop(&mut self) {
+        zeroize(&mut self.bytes);
+    }
+}
+
+// The public nonces D = d*G and E = e*G a signer sends to the coordinator in the first round.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SigningCommitments {
+    index: u32,
+    d: PublicKey,
+    e: PublicKey,
+}
+
+impl SigningCommitments {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    // index (4 bytes big endian) || D || E
+    pub fn serialize(&self) -> [u8; 70] {
+        let mut res = [0u8; 70];
+        res[..4].copy_from_slice(&self.index.to_be_bytes());
+        res[4..37].copy_from_slice(&self.d.compressed());
+        res[37..].copy_from_slice(&self.e.compressed());
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<SigningCommitments, &'static str> {
+        if ser.len() != 70 {
+            return Err("Signing commitments should be 70 bytes");
+        }
+        Ok(SigningCommitments {
+            index: parse_index(&ser[..4])?,
+            d: PublicKey::from_compressed(&ser[4..37])?,
+            e: PublicKey::from_compressed(&ser[37..])?,
+        })
+    }
+}
+
+// First signing round. `rand` must be fresh randomness for every call, the nonces must never be used twice.
+pub fn commit(key: &KeyPackage, rand: &[u8; 32]) -> (SigningNonces, SigningCommitments) {
+    let secp = get_context();
+    let seed = [&rand[..], &key.share.serialize()].concat();
+    let mut nonces = derive_scalars(b"FROST/nonce", &seed, 0..2);
+    let (d, e) = (nonces.next().unwrap(), nonces.next().unwrap());
+
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(&int_to_bytes(&d));
+    bytes[32..].copy_from_slice(&int_to_bytes(&e));
+    let commitments = SigningCommitments { index: key.index(), d: secp.mul_generator(&d).into(), e: secp.mul_generator(&e).into() };
+    (SigningNonces { index: key.index(), bytes }, commitments)
+}
+
+// What the coordinator sends to the chosen signers: their commitments (sorted by index) and the message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SigningPackage {
+    commitments: Vec<SigningCommitments>,
+    msg: Vec<u8>,
+}
+
+impl SigningPackage {
+    pub fn new(commitments: &[SigningCommitments], msg: &[u8]) -> Result<SigningPackage, &'static str> {
+        let mut commitments = commitments.to_vec();
+        commitments.sort_by_key(SigningCommitments::index);
+        if commitments.is_empty() {
+            return Err("A signing package needs at least one signer");
+        }
+        if commitments.windows(2).any(|pair| pair[0].index == pair[1].index) {
+            return Err("A signer appears twice in the signing package");
+        }
+        Ok(SigningPackage { commitments, msg: msg.to_vec() })
+    }
+
+    pub fn signers(&self) -> Vec<u32> {
+        self.commitments.iter().map(SigningCommitments::index).collect()
+    }
+
+    pub fn msg(&self) -> &[u8] {
+        &self.msg
+    }
+
+    // number of signers (4 bytes big endian) || commitments || message
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut res = (self.commitments.len() as u32).to_be_bytes().to_vec();
+        self.commitments.iter().for_each(|c| res.extend_from_slice(&c.serialize()));
+        res.extend_from_slice(&self.msg);
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<SigningPackage, &'static str> {
+        if ser.len() < 4 {
+            return Err("A signing package should be at least 4 bytes");
+        }
+        let count = u32::from_be_bytes([ser[0], ser[1], ser[2], ser[3]]) as usize;
+        let end = count.checked_mul(70).and_then(|len| len.checked_add(4)).filter(|end| *end <= ser.len());
+        let end = end.ok_or("The signing package is too short for its number of signers")?;
+        let commitments = ser[4..end].chunks(70).map(SigningCommitments::parse).collect::<Result<Vec<_>, _>>()?;
+        let package = SigningPackage::new(&commitments, &ser[end..])?;
+        if package.commitments != commitments {
+            return Err("The signers in a signing package should be sorted by index");
+        }
+        Ok(package)
+    }
+
+    fn commitments_of(&self, index: u32) -> Option<&SigningCommitments> {
+        self.commitments.iter().find(|c| c.index == index)
+    }
+}
+
+// A signer's answer in the second round.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureShare {
+    index: u32,
+    z: Integer,
+}
+
+impl SignatureShare {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    // index (4 bytes big endian) || z
+    pub fn serialize(&self) -> [u8; 36] {
+        let mut res = [0u8; 36];
+        res[..4].copy_from_slice(&self.index.to_be_bytes());
+        res[4..].copy_from_slice(&int_to_bytes(&self.z));
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<SignatureShare, &'static str> {
+        if ser.len() != 36 {
+            return Err("A signature share should be 36 bytes");
+        }
+        let z = int_from_bytes(&ser[4..]);
+        if z >= get_context().order {
+            return Err("The signature share overflows the curve order");
+        }
+        Ok(SignatureShare { index: parse_index(&ser[..4])?, z })
+    }
+}
+
+// The values every signer and the coordinator derive from a signing package.
+struct SigningContext {
+    // The binding factor of every signer, in the order of the package.
+    binding_factors: Vec<Integer>,
+    // The group nonce R = sum(D_i + rho_i*E_i).
+    r: PublicKey,
+    // The BIP340 challenge.
+    c: Integer,
+}
+
+impl SigningContext {
+    fn new(package: &SigningPackage, public: &PublicKeyPackage) -> SigningContext {
+        let secp = get_context();
+        let y = public.x_only_group_pubkey();
+        let msg_hash = tagged_hash(b"FROST/message", &package.msg);
+        let list: Vec<u8> = package.commitments.iter().flat_map(|c| c.serialize().to_vec()).collect();
+        let list_hash = tagged_hash(b"FROST/commitments", &list);
+
+        let binding_factors: Vec<_> = package
+            .commitments
+            .iter()
+            .map(|c| {
+                let data = [&y[..], &msg_hash, &list_hash, &c.index.to_be_bytes()].concat();
+                int_from_bytes(&tagged_hash(b"FROST/binding", &data)) % &secp.order
+            })
+            .collect();
+        let r =
+            package.commitments.iter().zip(binding_factors.iter()).fold(Point::from(PublicKey::zero_pubkey()), |r, (c, rho)| {
+                r + Point::from(c.d.clone()) + rho * Point::from(c.e.clone())
+            });
+        // Nobody knows the discrete log of R if it's infinity, so G is as good as any point.
+        let r = if r.is_on_infinity() { secp.generator() } else { r };
+        let c = bip340_challenge(&r.x.clone().serialize_num(), &y, &package.msg).num;
+        SigningContext { binding_factors, r: r.into(), c }
+    }
+
+    fn binding_factor(&self, package: &SigningPackage, index: u32) -> &Integer {
+        let position = package.commitments.iter().position(|c| c.index == index).unwrap();
+        &self.binding_factors[position]
+    }
+}
+
+// Second signing round: z_i = d_i + rho_i*e_i + lambda_i*s_i*c, with the nonces and the share
+// negated as needed so the result is a BIP340 signature for the even R and group key.
+pub fn sign(package: &SigningPackage, nonces: SigningNonces, key: &KeyPackage) -> Result<SignatureShare, &'static str> {
+    let secp = get_context();
+    let order = &secp.order;
+    if nonces.index != key.index() {
+        return Err("The nonces belong to another signer");
+    }
+    if package.commitments.len() < key.public.threshold() {
+        return Err("The signing package has fewer signers than the threshold");
+    }
+    let d = int_from_bytes(&nonces.bytes[..32]);
+    let e = int_from_bytes(&nonces.bytes[32..]);
+    match package.commitments_of(key.index()) {
+        Some(c) if Point::from(c.d.clone()) == secp.mul_generator(&d) && Point::from(c.e.clone()) == secp.mul_generator(&e) => (),
+        _ => return Err("The signing package doesn't contain this signer's commitments"),
+    }
+
+    let ctx = SigningContext::new(package, &key.public);
+    let (d, e) = if ctx.r.has_even_y() { (d, e) } else { (order - d, order - e) };
+    let s = &key.share.value;
+    let s = if key.public.group_pubkey().has_even_y() { s.clone() } else { Integer::from(order - s) };
+    let lambda = lagrange_coefficient(&package.signers(), key.index());
+    let z = (d + e * ctx.binding_factor(package, key.index()) + lambda * s * &ctx.c) % order;
+    Ok(SignatureShare { index: key.index(), z })
+}
+
+// Why `aggregate` failed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AggregateError {
+    // The signing package has fewer signers than the threshold.
+    NotEnoughSigners,
+    // The signers (by index) whose signature share is missing or invalid.
+    InvalidShares(Vec<u32>),
+}
+
+// z_i*G == R_i + c*lambda_i*Y_i, where R_i = D_i + rho_i*E_i and Y_i is the signer's verifying share.
+pub fn verify_share(package: &SigningPackage, share: &SignatureShare, public: &PublicKeyPackage) -> bool {
+    if package.commitments_of(share.index).is_none() {
+        return false;
+    }
+    verify_share_with(package, &SigningContext::new(package, public), share, public)
+}
+
+fn verify_share_with(package: &SigningPackage, ctx: &SigningContext, share: &SignatureShare, public: &PublicKeyPackage) -> bool {
+    let secp = get_context();
+    let commitments = package.commitments_of(share.index).unwrap();
+    let ri = Point::from(commitments.d.clone()) + ctx.binding_factor(package, share.index) * Point::from(commitments.e.clone());
+    let ri = if ctx.r.has_even_y() || ri.is_on_infinity() { ri } else { PublicKey::from(ri).negate().into() };
+    let mut cl = (&ctx.c * lagrange_coefficient(&package.signers(), share.index)) % &secp.order;
+    if !public.group_pubkey().has_even_y() {
+        cl = (&secp.order - cl) % &secp.order;
+    }
+    secp.mul_generator(&share.z) == ri + cl * Point::from(public.verifying_share(share.index))
+}
+
+// Verify every share and combine them into a BIP340 signature for the group key.
+// On failure names the signers to exclude from the next attempt.
+pub fn aggregate(
+    package: &SigningPackage,
+    shares: &[SignatureShare],
+    public: &PublicKeyPackage,
+) -> Result<SchnorrSignature, AggregateError> {
+    let order = &get_context().order;
+    if package.commitments.len() < public.threshold() {
+        return Err(AggregateError::NotEnoughSigners);
+    }
+    let ctx = SigningContext::new(package, public);
+    let mut z = Integer::new();
+    let mut faulty = Vec::new();
+    for index in package.signers() {
+        match shares.iter().find(|share| share.index == index) {
+            Some(share) if verify_share_with(package, &ctx, share, public) => z = (z + &share.z) % order,
+            _ => faulty.push(index),
+        }
+    }
+    if !faulty.is_empty() {
+        return Err(AggregateError::InvalidShares(faulty));
+    }
+    Ok(SchnorrSignature::new(&ctx.r.x_only(), &int_to_bytes(&z)))
+}
+
+// Scalars in [1, n-1] derived from seed || i for every i in `range`.
+fn derive_scalars<'a>(tag: &'a [u8], seed: &'a [u8], range: std::ops::Range<usize>) -> impl Iterator<Item = Integer> + 'a {
+    range.map(move |i| scalar_from_seed(tag, &[seed, &(i as u32).to_be_bytes()].concat()))
+}
+
+fn dkg_challenge(index: u32, c0: &PublicKey, r: &PublicKey) -> Integer {
+    let data = [&index.to_be_bytes()[..], &c0.compressed(), &r.compressed()].concat();
+    int_from_bytes(&tagged_hash(b"FROST/dkg challenge", &data)) % &get_context().order
+}
+
+fn check_threshold(threshold: usize, n: u32) -> Result<(), &'static str> {
+    if threshold == 0 || threshold > n as usize {
+        Err("The threshold must be in the range [1, n]")
+    } else {
+        Ok(())
+    }
+}
+
+fn parse_index(ser: &[u8]) -> Result<u32, &'static str> {
+    match u32::from_be_bytes([ser[0], ser[1], ser[2], ser[3]]) {
+        0 => Err("A participant index can't be zero"),
+        index => Ok(index),
+    }
+}
+
+fn parse_commitment(ser: &[u8]) -> Result<Vec<PublicKey>, &'static str> {
+    let chunks = ser.chunks_exact(33);
+    if ser.is_empty() || !chunks.remainder().is_empty() {
+        return Err("A commitment should be a non empty list of 33 byte public keys");
+    }
+    chunks.map(PublicKey::from_compressed).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dealer_keys(sk: &PrivateKey, threshold: usize, n: u32) -> Vec<KeyPackage> {
+        let (shares, public) = trusted_dealer_keygen(sk, threshold, n, &[0x42; 32]).unwrap();
+        shares.into_iter().map(|share| KeyPackage::new(share, public.clone()).unwrap()).collect()
+    }
+
+    fn run_dkg(threshold: usize, n: u32) -> Vec<KeyPackage> {
+        let (secrets, round1): (Vec<_>, Vec<_>) = (1..=n).map(|i| dkg_part1(i, threshold, n, &[i as u8; 32]).unwrap()).unzip();
+        // Everything goes through the wire format.
+        let round1: Vec<_> = round1.iter().map(|package| DkgRound1Package::parse(&package.serialize()).unwrap()).collect();
+
+        let (secrets, round2): (Vec<_>, Vec<_>) = secrets
+            .into_iter()
+            .map(|secret| {
+                let others: Vec<_> = round1.iter().filter(|package| package.index() != secret.index).cloned().collect();
+                dkg_part2(secret, &others).unwrap()
+            })
+            .unzip();
+        let round2: Vec<_> = round2.concat().iter().map(|package| DkgRound2Package::parse(&package.serialize()).unwrap()).collect();
+
+        secrets
+            .into_iter()
+            .map(|secret| {
+                let received: Vec<_> = round2.iter().filter(|package| package.receiver() == secret.index).cloned().collect();
+                dkg_part3(secret, &received).unwrap()
+            })
+            .collect()
+    }
+
+    fn sign_with(keys: &[&KeyPackage], msg: &[u8], seed: u8) -> (SigningPackage, Vec<SignatureShare>) {
+        let (nonces, commitments): (Vec<_>, Vec<_>) = keys.iter().map(|key| commit(key, &[seed ^ key.index() as u8; 32])).unzip();
+        let commitments: Vec<_> = commitments.iter().map(|c| SigningCommitments::parse(&c.serialize()).unwrap()).collect();
+        let package = SigningPackage::new(&commitments, msg).unwrap();
+        let package = SigningPackage::parse(&package.serialize()).unwrap();
+
+        let shares = keys.iter().zip(nonces).map(|(key, nonces)| sign(&package, nonces, key).unwrap());
+        let shares = shares.map(|share| SignatureShare::parse(&share.serialize()).unwrap()).collect();
+        (package, shares)
+    }
+
+    #[test]
+    fn test_trusted_dealer() {
+        // Both parities of the group key.
+        for sk in 1..=2u8 {
+            let sk = PrivateKey::from_serialized(&[sk; 32]);
+            let keys = dealer_keys(&sk, 2, 3);
+            let public = keys[0].public();
+            assert_eq!(public.group_pubkey(), &sk.generate_pubkey());
+            assert_eq!(PublicKeyPackage::parse(&public.serialize()).unwrap(), *public);
+
+            for (i, j) in &[(0, 1), (0, 2), (1, 2)] {
+                for (seed, msg) in [&b"first"[..], b"second", b"third"].iter().enumerate() {
+                    let (package, shares) = sign_with(&[&keys[*i], &keys[*j]], msg, seed as u8);
+                    let sig = aggregate(&package, &shares, public).unwrap();
+                    let group_pubkey = PublicKey::from_xonly(&public.x_only_group_pubkey()).unwrap();
+                    assert!(group_pubkey.verify_schnorr_msg(msg, sig));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dkg() {
+        let keys = run_dkg(3, 5);
+        let public = keys[0].public();
+        assert!(keys.iter().all(|key| key.public() == public));
+        for (index, key) in keys.iter().enumerate() {
+            assert_eq!(key.index(), index as u32 + 1);
+            assert_eq!(public.verifying_share(key.index()), PrivateKey::new(key.share.value.clone()).generate_pubkey());
+        }
+
+        let msg = b"signed by a DKG group";
+        let group_pubkey = PublicKey::from_xonly(&public.x_only_group_pubkey()).unwrap();
+        for signers in &[[0, 1, 2], [1, 3, 4], [0, 2, 4]] {
+            let (package, shares) = sign_with(&[&keys[signers[0]], &keys[signers[1]], &keys[signers[2]]], msg, 7);
+            assert!(group_pubkey.verify_schnorr_msg(msg, aggregate(&package, &shares, public).unwrap()));
+        }
+        let (package, shares) = sign_with(&keys.iter().collect::<Vec<_>>(), msg, 9);
+        assert!(group_pubkey.verify_schnorr_msg(msg, aggregate(&package, &shares, public).unwrap()));
+    }
+
+    #[test]
+    fn test_dkg_misbehavior() {
+        let (secrets, mut round1): (Vec<_>, Vec<_>) = (1..=3).map(|i| dkg_part1(i, 2, 3, &[i as u8; 32]).unwrap()).unzip();
+        let mut secrets = secrets.into_iter();
+        let first = secrets.next().unwrap();
+
+        // Participant 3 copies participant 2's constant term without knowing its discrete log.
+        let mut rogue = round1[2].clone();
+        rogue.commitment[0] = round1[1].commitment[0].clone();
+        assert!(dkg_part2(first, &[round1[1].clone(), rogue]).is_err());
+
+        let second = secrets.next().unwrap();
+        assert!(dkg_part2(second, &round1[..1]).is_err());
+
+        let third = secrets.next().unwrap();
+        round1[0].commitment.pop();
+        assert!(dkg_part2(third, &[round1[0].clone(), round1[1].clone()]).is_err());
+    }
+
+    #[test]
+    fn test_dkg_bad_share() {
+        let (secrets, round1): (Vec<_>, Vec<_>) = (1..=3).map(|i| dkg_part1(i, 2, 3, &[i as u8; 32]).unwrap()).unzip();
+        let (secrets, round2): (Vec<_>, Vec<_>) = secrets
+            .into_iter()
+            .map(|secret| {
+                let others: Vec<_> = round1.iter().filter(|package| package.index() != secret.index).cloned().collect();
+                dkg_part2(secret, &others).unwrap()
+            })
+            .unzip();
+        let mut round2 = round2.concat();
+        let received =
+            |round2: &[DkgRound2Package], i| round2.iter().filter(|package| package.receiver() == i).cloned().collect::<Vec<_>>();
+
+        let mut secrets = secrets.into_iter();
+        let first = secrets.next().unwrap();
+        let tampered = round2.iter_mut().find(|package| package.receiver() == 1).unwrap();
+        tampered.share += 1;
+        assert!(dkg_part3(first, &received(&round2, 1)).is_err());
+        let second = secrets.next().unwrap();
+        assert!(dkg_part3(second, &received(&round2, 2)[..1]).is_err());
+        let third = secrets.next().unwrap();
+        assert!(dkg_part3(third, &received(&round2, 3)).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_shares() {
+        let keys = dealer_keys(&PrivateKey::from_serialized(&[3; 32]), 3, 4);
+        let public = keys[0].public();
+        let msg = b"blame";
+        let (package, mut shares) = sign_with(&[&keys[0], &keys[1], &keys[3]], msg, 1);
+        assert!(shares.iter().all(|share| verify_share(&package, share, public)));
+
+        shares[1].z += 1;
+        shares.pop();
+        assert!(!verify_share(&package, &shares[1], public));
+        assert_eq!(aggregate(&package, &shares, public), Err(AggregateError::InvalidShares(vec![2, 4])));
+
+        // Honest signers refuse to sign this package.
+        let package = SigningPackage::new(&[commit(&keys[0], &[2; 32]).1, commit(&keys[1], &[2; 32]).1], msg).unwrap();
+        assert_eq!(aggregate(&package, &[], public), Err(AggregateError::NotEnoughSigners));
+    }
+
+    #[test]
+    fn test_sign_errors() {
+        let keys = dealer_keys(&PrivateKey::from_serialized(&[4; 32]), 2, 3);
+        let msg = b"errors";
+        let (nonces, commitments): (Vec<_>, Vec<_>) = keys.iter().map(|key| commit(key, &[key.index() as u8; 32])).unzip();
+        let mut nonces = nonces.into_iter();
+
+        let package = SigningPackage::new(&commitments[..1], msg).unwrap();
+        assert!(sign(&package, nonces.next().unwrap(), &keys[0]).is_err());
+        let package = SigningPackage::new(&commitments[1..], msg).unwrap();
+        assert!(sign(&package, nonces.next().unwrap(), &keys[2]).is_err());
+        let (fresh, _) = commit(&keys[2], &[9; 32]);
+        assert!(sign(&package, fresh, &keys[2]).is_err());
+        assert!(sign(&package, nonces.next().unwrap(), &keys[2]).is_ok());
+
+        assert!(SigningPackage::new(&[commitments[0].clone(), commitments[0].clone()], msg).is_err());
+        assert!(SigningPackage::new(&[], msg).is_err());
+        let mut unsorted = package.serialize();
+        unsorted[4..144].rotate_left(70);
+        assert!(SigningPackage::parse(&unsorted).is_err());
+    }
+
+    #[test]
+    fn test_lagrange_interpolation() {
+        let sk = PrivateKey::from_serialized(&[5; 32]);
+        let (shares, _) = trusted_dealer_keygen(&sk, 3, 5, &[1; 32]).unwrap();
+        let order = &get_context().order;
+        for signers in &[[1u32, 2, 3], [2, 4, 5], [5, 1, 3]] {
+            let secret = signers
+                .iter()
+                .map(|i| lagrange_coefficient(signers, *i) * &shares[*i as usize - 1].value)
+                .fold(Integer::new(), |acc, term| (acc + term) % order);
+            assert_eq!(secret, sk.scalar);
+        }
+        assert!(SecretShare::parse(&shares[0].serialize())
+            .unwrap()
+            .verify(&trusted_dealer_keygen(&sk, 3, 5, &[1; 32]).unwrap().1.commitment));
+        assert!(trusted_dealer_keygen(&sk, 0, 5, &[1; 32]).is_err());
+        assert!(trusted_dealer_keygen(&sk, 6, 5, &[1; 32]).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::point::Point;
+use crate::secp256k1::{bip340_challenge, get_context, PublicKey, SchnorrSignature};
+use crate::utility::{int_from_bytes, int_to_bytes, tagged_hash};
+use rug::Integer;
+
+// Non-interactive half-aggregation of BIP340 signatures (the cross-input signature aggregation draft).
+// n signatures (R_i, s_i) on distinct keys and messages compress to R_0 || ... || R_(n-1) || s, 32*(n+1) bytes,
+// with s = sum(z_i * s_i). The random looking z_i commit to every key, message and R up to i, so the s_i
+// can't be shuffled around. Anyone can aggregate, without knowing any of the private keys.
+// Keys are x-only and messages are 32 bytes, like in the draft.
+
+// The draft limits the number of signatures so the index fits in 2 bytes.
+const MAX_SIGNATURES: usize = 1 << 16;
+
+// Aggregate the signatures, each of which should have been verified before.
+pub fn aggregate(pms: &[(PublicKey, [u8; 32], SchnorrSignature)]) -> Result<Vec<u8>, &'static str> {
+    inc_aggregate(&[0u8; 32], &[], pms)
+}
+
+// Add more signatures to an aggregate signature of the keys and messages `pm_aggd`.
+pub fn inc_aggregate(
+    aggsig: &[u8],
+    pm_aggd: &[(PublicKey, [u8; 32])],
+    pms_to_agg: &[(PublicKey, [u8; 32], SchnorrSignature)],
+) -> Result<Vec<u8>, &'static str> {
+    let order = &get_context().order;
+    let (v, u) = (pm_aggd.len(), pms_to_agg.len());
+    if v + u >= MAX_SIGNATURES {
+        return Err("Too many signatures to aggregate");
+    }
+    if aggsig.len() != 32 * (v + 1) {
+        return Err("The aggregate signature doesn't match the number of messages");
+    }
+    let sigs: Vec<_> = pms_to_agg.iter().map(|(_, _, sig)| sig.serialize()).collect();
+    let rs: Vec<&[u8]> = aggsig[..32 * v].chunks(32).chain(sigs.iter().map(|sig| &sig[..32])).collect();
+    let pms: Vec<_> = pm_aggd.iter().cloned().chain(pms_to_agg.iter().map(|(pk, msg, _)| (pk.clone(), *msg))).collect();
+    let z = randomizers(&rs, &pms);
+
+    let mut s = int_from_bytes(&aggsig[32 * v..]);
+    if s >= *order {
+        return Err("The aggregate s overflows the curve order");
+    }
+    for (sig, z) in sigs.iter().zip(&z[v..]) {
+        let s_i = int_from_bytes(&sig[32..]);
+        if s_i >= *order {
+            return Err("A signature s overflows the curve order");
+        }
+        s = (s + s_i * z) % order;
+    }
+    let mut res: Vec<u8> = rs.concat();
+    res.extend_from_slice(&int_to_bytes(&s));
+    Ok(res)
+}
+
+// Verify the aggregate signature of all the keys and messages:
+// s*G = sum(z_i * (R_i + e_i*P_i)), checked in a single multi-scalar multiplication.
+pub fn verify_aggregate(aggsig: &[u8], pm_aggd: &[(PublicKey, [u8; 32])]) -> bool {
+    let secp = get_context();
+    let u = pm_aggd.len();
+    if u >= MAX_SIGNATURES || aggsig.len() != 32 * (u + 1) {
+        return false;
+    }
+    let s = int_from_bytes(&aggsig[32 * u..]);
+    if s >= secp.order {
+        return false;
+    }
+    let rs: Vec<_> = aggsig[..32 * u].chunks(32).collect();
+    let z = randomizers(&rs, pm_aggd);
+
+    let mut terms = Vec::with_capacity(2 * u);
+    for ((r, (pk, msg)), z) in rs.iter().zip(pm_aggd).zip(z) {
+        // Like in BIP340 the R and the key are the points with the even y.
+        let (r_point, p) = match (PublicKey::from_xonly(r), PublicKey::from_xonly(&pk.x_only())) {
+            (Ok(r_point), Ok(p)) => (r_point, p),
+            _ => return false,
+        };
+        let e = bip340_challenge(r, &p.x_only(), msg).num;
+        terms.push((&z * e, Point::from(p)));
+        terms.push((z, Point::from(r_point)));
+    }
+    secp.mul_generator(&s) == secp.multi_mul(&terms)
+}
+
+// z_0 = 1, z_i = hash_HalfAgg/randomizer(R_0 || pk_0 || m_0 || ... || R_i || pk_i || m_i) mod n.
+fn randomizers(rs: &[&[u8]], pms: &[(PublicKey, [u8; 32])]) -> Vec<Integer> {
+    let order = &get_context().order;
+    let mut data = Vec::with_capacity(96 * pms.len());
+    rs.iter()
+        .zip(pms)
+        .enumerate()
+        .map(|(i, (r, (pk, msg)))| {
+            data.extend_from_slice(r);
+            data.extend_from_slice(&pk.x_only());
+            data.extend_from_slice(msg);
+            if i == 0 {
+                Integer::from(1)
+            } else {
+                int_from_bytes(&tagged_hash(b"HalfAgg/randomizer", &data)) % order
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secp256k1::PrivateKey;
+
+    fn sign_many(amount: u8) -> Vec<(PublicKey, [u8; 32], SchnorrSignature)> {
+        (1..=amount)
+            .map(|i| {
+                let sk = PrivateKey::from_serialized(&[i; 32]);
+                let msg = tagged_hash(b"HalfAgg test", &[i]);
+                (sk.generate_pubkey(), msg, sk.sign_schnorr_msg(&msg).unwrap())
+            })
+            .collect()
+    }
+
+    fn keys_and_msgs(pms: &[(PublicKey, [u8; 32], SchnorrSignature)]) -> Vec<(PublicKey, [u8; 32])> {
+        pms.iter().map(|(pk, msg, _)| (pk.clone(), *msg)).collect()
+    }
+
+    #[test]
+    fn test_half_aggregation() {
+        let pms = sign_many(5);
+        let pm_aggd = keys_and_msgs(&pms);
+        let aggsig = aggregate(&pms).unwrap();
+        assert_eq!(aggsig.len(), 32 * 6);
+        assert!(verify_aggregate(&aggsig, &pm_aggd));
+
+        // Aggregating in steps gives the same result.
+        let first = aggregate(&pms[..2]).unwrap();
+        assert!(verify_aggregate(&first, &pm_aggd[..2]));
+        assert_eq!(inc_aggregate(&first, &pm_aggd[..2], &pms[2..]).unwrap(), aggsig);
+
+        // A single signature aggregates to itself.
+        let single = aggregate(&pms[..1]).unwrap();
+        assert_eq!(single[..], pms[0].2.serialize()[..]);
+        // Nothing aggregates to zero.
+        let empty = aggregate(&[]).unwrap();
+        assert_eq!(empty, vec![0u8; 32]);
+        assert!(verify_aggregate(&empty, &[]));
+    }
+
+    #[test]
+    fn test_invalid_aggregate() {
+        let mut pms = sign_many(4);
+        let pm_aggd = keys_and_msgs(&pms);
+        let aggsig = aggregate(&pms).unwrap();
+
+        let mut reordered = pm_aggd.clone();
+        reordered.swap(1, 2);
+        assert!(!verify_aggregate(&aggsig, &reordered));
+        assert!(!verify_aggregate(&aggsig, &pm_aggd[..3]));
+        let mut wrong_msg = pm_aggd.clone();
+        wrong_msg[3].1[0] ^= 1;
+        assert!(!verify_aggregate(&aggsig, &wrong_msg));
+        let mut tampered = aggsig.clone();
+        tampered[32 * 4 + 31] ^= 1;
+        assert!(!verify_aggregate(&tampered, &pm_aggd));
+        let mut overflow = aggsig.clone();
+        overflow[32 * 4..].copy_from_slice(&[0xff; 32]);
+        assert!(!verify_aggregate(&overflow, &pm_aggd));
+
+        // An invalid signature makes the aggregate invalid.
+        let mut sig = pms[1].2.serialize();
+        sig[63] ^= 1;
+        pms[1].2 = SchnorrSignature::parse(sig);
+        assert!(!verify_aggregate(&aggregate(&pms).unwrap(), &pm_aggd));
+
+        assert!(inc_aggregate(&aggsig, &pm_aggd[..3], &[]).is_err());
+        assert!(inc_aggregate(&aggsig[..32], &pm_aggd, &[]).is_err());
+    }
+}
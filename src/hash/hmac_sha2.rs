@@ -6,6 +6,11 @@ pub struct HmacSha256 {
     outer: HashDigest,
 }
 
+// HKDF with HMAC-SHA256 (RFC 5869), the pseudorandom key from the extract step.
+pub struct Hkdf {
+    prk: [u8; 32],
+}
+
 pub struct HmacSha256Drbg {
     pub(crate) k: [u8; 32],
     pub(crate) v: [u8; 32],
@@ -56,6 +61,37 @@ impl HmacSha256 {
     }
 }
 
+impl Hkdf {
+    pub fn extract(salt: &[u8], ikm: &[u8]) -> Self {
+        let mut hmac = HmacSha256::new(salt);
+        hmac.input(ikm);
+        Self { prk: hmac.finalize() }
+    }
+
+    pub fn prk(&self) -> [u8; 32] {
+        self.prk
+    }
+
+    // T(i) = HMAC(prk, T(i-1) || info || i), the output is T(1) || T(2) || ... cut to `len` bytes.
+    pub fn expand(&self, info: &[u8], len: usize) -> Result<Vec<u8>, &'static str> {
+        if len > 255 * 32 {
+            return Err("HKDF can't expand to more than 255 blocks");
+        }
+        let mut res = Vec::with_capacity(len + 32);
+        let mut t = Vec::new();
+        for i in 1..=(len + 31) / 32 {
+            let mut hmac = HmacSha256::new(&self.prk);
+            hmac.input(&t);
+            hmac.input(info);
+            hmac.input(&[i as u8]);
+            t = hmac.finalize().to_vec();
+            res.extend_from_slice(&t);
+        }
+        res.truncate(len);
+        Ok(res)
+    }
+}
+
 impl HmacSha256Drbg {
     pub fn new(seed: &[u8], seed2: Option<&[u8]>) -> Self {
         let k = [0u8; 32];
@@ -155,6 +191,21 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_hkdf_test_vectors() {
+        // RFC 5869 test cases 1 and 3.
+        let hkdf = Hkdf::extract(&hex("000102030405060708090a0b0c"), &[0x0b; 22]);
+        assert_eq!(hkdf.prk()[..], hex("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5")[..]);
+        let okm = hkdf.expand(&hex("f0f1f2f3f4f5f6f7f8f9"), 42).unwrap();
+        assert_eq!(okm, hex("3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"));
+
+        let hkdf = Hkdf::extract(&[], &[0x0b; 22]);
+        assert_eq!(hkdf.prk()[..], hex("19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04")[..]);
+        let okm = hkdf.expand(&[], 42).unwrap();
+        assert_eq!(okm, hex("8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"));
+        assert!(hkdf.expand(&[], 255 * 32 + 1).is_err());
+    }
+
     fn test_vector<A: AsRef<[u8]>, B: AsRef<[u8]>, C: AsRef<[u8]>>(key: A, data: B, res: C, len: Option<usize>) -> bool {
         let key = key.as_ref();
         let data = data.as_ref();
@@ -0,0 +1,330 @@
+use crate::field::{mod_and_new, FieldElement};
+use crate::hash::{HashDigest, HashTrait};
+use crate::point::Point;
+use crate::secp256k1::{fe_from_hex, get_context, PublicKey};
+use crate::utility::int_from_bytes;
+use rug::Integer;
+
+// Hashing to the curve, the secp256k1_XMD:SHA-256_SSWU_RO_ and secp256k1_XMD:SHA-256_SSWU_NU_ suites of RFC 9380.
+// The resulting points have no known discrete log, which is what Pedersen generators, VRFs and OPRFs need.
+// secp256k1 has A = 0, so the simplified SWU map can't be used on it directly. It maps to the 3-isogenous curve
+// E': y^2 = x^3 + A'x + B' instead, and the isogeny takes the point back to secp256k1.
+
+// The constants of section 8.7 and appendix E.1.
+const ISO_A: &str = "3f8731abdd661adca08a5558f0f5d272e953d363cb6f0e5d405447c01a444533";
+const ISO_B: u32 = 1771;
+// Z = -11
+const Z: i32 = -11;
+const X_NUM: [&str; 4] = [
+    "8e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38daaaaa8c7",
+    "07d3d4c80bc321d5b9f315cea7fd44c5d595d2fc0bf63b92dfff1044f17c6581",
+    "534c328d23f234e6e2a413deca25caece4506144037c40314ecbd0b53d9dd262",
+    "8e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38daaaaa88c",
+];
+const X_DEN: [&str; 3] = [
+    "d35771193d94918a9ca34ccbb7b640dd86cd409542f8487d9fe6b745781eb49b",
+    "edadc6f64383dc1df7c4b2d51b54225406d36b641f5e41bbc52a56612a8c6d14",
+    "0000000000000000000000000000000000000000000000000000000000000001",
+];
+const Y_NUM: [&str; 4] = [
+    "4bda12f684bda12f684bda12f684bda12f684bda12f684bda12f684b8e38e23c",
+    "c75e0c32d5cb7c0fa9d0a54b12a0a6d5647ab046d686da6fdffc90fc201d71a3",
+    "29a6194691f91a73715209ef6512e576722830a201be2018a765e85a9ecee931",
+    "2f684bda12f684bda12f684bda12f684bda12f684bda12f684bda12f38e38d84",
+];
+const Y_DEN: [&str; 4] = [
+    "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffff93b",
+    "7a06534bb8bdb49fd5e9e6632722c2989467c1bfc8e8d978dfb425d2685c2573",
+    "6484aa716545ca2cf3a70c3fa8fe337e0a3d21162f0d6299a7bf8192bfd2a76f",
+    "0000000000000000000000000000000000000000000000000000000000000001",
+];
+
+// DSTs longer than this are hashed down first.
+const MAX_DST_LEN: usize = 255;
+
+// expand_message_xmd with SHA256, section 5.3.1.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Result<Vec<u8>, &'static str> {
+    let ell = (len + 31) / 32;
+    if ell > 255 || len > 0xffff {
+        return Err("The requested length is too long for expand_message_xmd");
+    }
+    let hashed_dst;
+    let dst = if dst.len() > MAX_DST_LEN {
+        hashed_dst = [&b"H2C-OVERSIZE-DST-"[..], dst].concat().hash_digest();
+        &hashed_dst[..]
+    } else {
+        dst
+    };
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+
+    let mut hasher = HashDigest::new();
+    hasher.input(&[0u8; 64]);
+    hasher.input(msg);
+    hasher.input(&(len as u16).to_be_bytes());
+    hasher.input(&[0]);
+    hasher.input(&dst_prime);
+    let b_0 = hasher.result();
+
+    let mut res = Vec::with_capacity(ell * 32);
+    let mut b_i = [0u8; 32];
+    for i in 1..=ell {
+        let mut hasher = HashDigest::new();
+        let xored: Vec<u8> = b_0.iter().zip(&b_i).map(|(a, b)| a ^ b).collect();
+        hasher.input(&xored);
+        hasher.input(&[i as u8]);
+        hasher.input(&dst_prime);
+        b_i = hasher.result();
+        res.extend_from_slice(&b_i);
+    }
+    res.truncate(len);
+    Ok(res)
+}
+
+// The random oracle suite: hash to two field elements and add their points, the output is uniform on the curve.
+pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Result<PublicKey, &'static str> {
+    let u = hash_to_field(msg, dst, 2)?;
+    let point = map_to_curve(&u[0]) + map_to_curve(&u[1]);
+    if point.is_on_infinity() {
+        return Err("The message hashed to the point at infinity");
+    }
+    // secp256k1 has cofactor 1, so there's no cofactor to clear.
+    Ok(point.into())
+}
+
+// The nonuniform encoding: a single field element, cheaper but the output isn't uniform on the curve.
+pub fn encode_to_curve(msg: &[u8], dst: &[u8]) -> Result<PublicKey, &'static str> {
+    let u = hash_to_field(msg, dst, 1)?;
+    let point = map_to_curve(&u[0]);
+    if point.is_on_infinity() {
+        return Err("The message encoded to the point at infinity");
+    }
+    Ok(point.into())
+}
+
+// `count` field elements of 48 bytes each, so the bias of the reduction mod p is negligible.
+fn hash_to_field(msg: &[u8], dst: &[u8], count: usize) -> Result<Vec<FieldElement>, &'static str> {
+    let modulo = &get_context().modulo;
+    let bytes = expand_message_xmd(msg, dst, count * 48)?;
+    Ok(bytes.chunks(48).map(|chunk| FieldElement::new(int_from_bytes(chunk), modulo)).collect())
+}
+
+fn map_to_curve(u: &FieldElement) -> Point {
+    let (x, y) = map_to_isogenous_curve(u);
+    iso_map(&x, &y)
+}
+
+// The simplified SWU map to E', section 6.6.2.
+fn map_to_isogenous_curve(u: &FieldElement) -> (FieldElement, FieldElement) {
+    let modulo = &u.modulo;
+    let (a, b) = (fe_from_hex(ISO_A), FieldElement::new(ISO_B, modulo));
+    let z = mod_and_new(Integer::from(Z), modulo);
+    let g = |x: &FieldElement| x.clone().pow_u(3) + a.clone() * x + &b;
+
+    let z_u2 = z.clone() * u.clone().pow_u(2);
+    // tv1 = inv0(Z^2 * u^4 + Z * u^2)
+    let den = z_u2.clone().pow_u(2) + &z_u2;
+    let x1 = if den.is_zero() {
+        b.clone() / (z * &a)
+    } else {
+        let tv1 = FieldElement::new(1, modulo) / den;
+        let mut minus_b = b.clone();
+        minus_b.reflect();
+        minus_b / &a * (tv1 + 1)
+    };
+    let gx1 = g(&x1);
+    let (x, gx) = if gx1.is_square() {
+        (x1, gx1)
+    } else {
+        let x2 = z_u2 * &x1;
+        let gx2 = g(&x2);
+        (x2, gx2)
+    };
+    let mut y = gx;
+    y.sqrt();
+    // sgn0(y) = sgn0(u)
+    if y.is_even() != u.is_even() {
+        y.reflect();
+    }
+    (x, y)
+}
+
+// The 3-isogeny from E' to secp256k1, appendix E.1.
+fn iso_map(x: &FieldElement, y: &FieldElement) -> Point {
+    let secp = get_context();
+    let zero = secp.generator().gen_zero();
+    let poly = |coeffs: &[&str]| coeffs.iter().rev().fold(FieldElement::new(0, &x.modulo), |acc, k| acc * x + fe_from_hex(k));
+    let (x_den, y_den) = (poly(&X_DEN), poly(&Y_DEN));
+    if x_den.is_zero() || y_den.is_zero() {
+        return zero;
+    }
+    let x = poly(&X_NUM) / x_den;
+    let y = y.clone() * poly(&Y_NUM) / y_den;
+    Point { x, y, group: zero.group }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utility::hex_to_bytes;
+
+    const RO_DST: &[u8] = b"QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_RO_";
+    const NU_DST: &[u8] = b"QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_NU_";
+
+    fn point(x: &str, y: &str) -> PublicKey {
+        get_context().get_pubkey(&hex_to_bytes(x), &hex_to_bytes(y))
+    }
+
+    // The messages of the RFC 9380 test vectors.
+    fn messages() -> [Vec<u8>; 5] {
+        [
+            b"".to_vec(),
+            b"abc".to_vec(),
+            b"abcdef0123456789".to_vec(),
+            [&b"q128_"[..], &[b'q'; 128]].concat(),
+            [&b"a512_"[..], &[b'a'; 512]].concat(),
+        ]
+    }
+
+    #[test]
+    fn test_expand_message_xmd() {
+        // RFC 9380 appendix K.1, with len_in_bytes 0x20 and 0x80.
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let vectors = [
+            [
+                "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+                "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbee0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dcc541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced",
+            ],
+            [
+                "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+                "abba86a6129e366fc877aab32fc4ffc70120d8996c88aee2fe4b32d6c7b6437a647e6c3163d40b76a73cf6a5674ef1d890f95b664ee0afa5359a5c4e07985635bbecbac65d747d3d2da7ec2b8221b17b0ca9dc8a1ac1c07ea6a1e60583e2cb00058e77b7b72a298425cd1b941ad4ec65e8afc50303a22c0f99b0509b4c895f40",
+            ],
+            [
+                "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1",
+                "ef904a29bffc4cf9ee82832451c946ac3c8f8058ae97d8d629831a74c6572bd9ebd0df635cd1f208e2038e760c4994984ce73f0d55ea9f22af83ba4734569d4bc95e18350f740c07eef653cbb9f87910d833751825f0ebefa1abe5420bb52be14cf489b37fe1a72f7de2d10be453b2c9d9eb20c7e3f6edc5a60629178d9478df",
+            ],
+            [
+                "b23a1d2b4d97b2ef7785562a7e8bac7eed54ed6e97e29aa51bfe3f12ddad1ff9",
+                "80be107d0884f0d881bb460322f0443d38bd222db8bd0b0a5312a6fedb49c1bbd88fd75d8b9a09486c60123dfa1d73c1cc3169761b17476d3c6b7cbbd727acd0e2c942f4dd96ae3da5de368d26b32286e32de7e5a8cb2949f866a0b80c58116b29fa7fabb3ea7d520ee603e0c25bcaf0b9a5e92ec6a1fe4e0391d1cdbce8c68a",
+            ],
+            [
+                "4623227bcc01293b8c130bf771da8c298dede7383243dc0993d2d94823958c4c",
+                "546aff5444b5b79aa6148bd81728704c32decb73a3ba76e9e75885cad9def1d06d6792f8a7d12794e90efed817d96920d728896a4510864370c207f99bd4a608ea121700ef01ed879745ee3e4ceef777eda6d9e5e38b90c86ea6fb0b36504ba4a45d22e86f6db5dd43d98a294bebb9125d5b794e9d2a81181066eb954966a487",
+            ],
+        ];
+        for (msg, [short, long]) in messages().iter().zip(vectors.iter()) {
+            assert_eq!(expand_message_xmd(msg, dst, 0x20).unwrap(), hex_to_bytes(short));
+            assert_eq!(expand_message_xmd(msg, dst, 0x80).unwrap(), hex_to_bytes(long));
+        }
+        assert_eq!(expand_message_xmd(b"abc", dst, 100).unwrap().len(), 100);
+        assert!(expand_message_xmd(b"abc", dst, 256 * 32).is_err());
+    }
+
+    #[test]
+    fn test_hash_to_curve_vectors() {
+        // RFC 9380 appendix J.8.1: u0, u1, Q0, Q1 and P.
+        let vectors = [
+            [
+                "6b0f9910dd2ba71c78f2ee9f04d73b5f4c5f7fc773a701abea1e573cab002fb3",
+                "1ae6c212e08fe1a5937f6202f929a2cc8ef4ee5b9782db68b0d5799fd8f09e16",
+                "74519ef88b32b425a095e4ebcc84d81b64e9e2c2675340a720bb1a1857b99f1e",
+                "c174fa322ab7c192e11748beed45b508e9fdb1ce046dee9c2cd3a2a86b410936",
+                "44548adb1b399263ded3510554d28b4bead34b8cf9a37b4bd0bd2ba4db87ae63",
+                "96eb8e2faf05e368efe5957c6167001760233e6dd2487516b46ae725c4cce0c6",
+                "c1cae290e291aee617ebaef1be6d73861479c48b841eaba9b7b5852ddfeb1346",
+                "64fa678e07ae116126f08b022a94af6de15985c996c3a91b64c406a960e51067",
+            ],
+            [
+                "128aab5d3679a1f7601e3bdf94ced1f43e491f544767e18a4873f397b08a2b61",
+                "5897b65da3b595a813d0fdcc75c895dc531be76a03518b044daaa0f2e4689e00",
+                "07dd9432d426845fb19857d1b3a91722436604ccbbbadad8523b8fc38a5322d7",
+                "604588ef5138cffe3277bbd590b8550bcbe0e523bbaf1bed4014a467122eb33f",
+                "e9ef9794d15d4e77dde751e06c182782046b8dac05f8491eb88764fc65321f78",
+                "cb07ce53670d5314bf236ee2c871455c562dd76314aa41f012919fe8e7f717b3",
+                "3377e01eab42db296b512293120c6cee72b6ecf9f9205760bd9ff11fb3cb2c4b",
+                "7f95890f33efebd1044d382a01b1bee0900fb6116f94688d487c6c7b9c8371f6",
+            ],
+            [
+                "ea67a7c02f2cd5d8b87715c169d055a22520f74daeb080e6180958380e2f98b9",
+                "7434d0d1a500d38380d1f9615c021857ac8d546925f5f2355319d823a478da18",
+                "576d43ab0260275adf11af990d130a5752704f79478628761720808862544b5d",
+                "643c4a7fb68ae6cff55edd66b809087434bbaff0c07f3f9ec4d49bb3c16623c3",
+                "f89d6d261a5e00fe5cf45e827b507643e67c2a947a20fd9ad71039f8b0e29ff8",
+                "b33855e0cc34a9176ead91c6c3acb1aacb1ce936d563bc1cee1dcffc806caf57",
+                "bac54083f293f1fe08e4a70137260aa90783a5cb84d3f35848b324d0674b0e3a",
+                "4436476085d4c3c4508b60fcf4389c40176adce756b398bdee27bca19758d828",
+            ],
+            [
+                "eda89a5024fac0a8207a87e8cc4e85aa3bce10745d501a30deb87341b05bcdf5",
+                "dfe78cd116818fc2c16f3837fedbe2639fab012c407eac9dfe9245bf650ac51d",
+                "9c91513ccfe9520c9c645588dff5f9b4e92eaf6ad4ab6f1cd720d192eb58247a",
+                "c7371dcd0134412f221e386f8d68f49e7fa36f9037676e163d4a063fbf8a1fb8",
+                "10fee3284d7be6bd5912503b972fc52bf4761f47141a0015f1c6ae36848d869b",
+                "0b163d9b4bf21887364332be3eff3c870fa053cf508732900fc69a6eb0e1b672",
+                "e2167bc785333a37aa562f021f1e881defb853839babf52a7f72b102e41890e9",
+                "f2401dd95cc35867ffed4f367cd564763719fbc6a53e969fb8496a1e6685d873",
+            ],
+            [
+                "8d862e7e7e23d7843fe16d811d46d7e6480127a6b78838c277bca17df6900e9f",
+                "68071d2530f040f081ba818d3c7188a94c900586761e9115efa47ae9bd847938",
+                "b32b0ab55977b936f1e93fdc68cec775e13245e161dbfe556bbb1f72799b4181",
+                "2f5317098360b722f132d7156a94822641b615c91f8663be69169870a12af9e8",
+                "148f98780f19388b9fa93e7dc567b5a673e5fca7079cd9cdafd71982ec4c5e12",
+                "3989645d83a433bc0c001f3dac29af861f33a6fd1e04f4b36873f5bff497298a",
+                "e3c8d35aaaf0b9b647e88a0a0a7ee5d5bed5ad38238152e4e6fd8c1f8cb7c998",
+                "8446eeb6181bf12f56a9d24e262221cc2f0c4725c7e3803024b5888ee5823aa6",
+            ],
+        ];
+        for (msg, v) in messages().iter().zip(vectors.iter()) {
+            let u = hash_to_field(msg, RO_DST, 2).unwrap();
+            assert_eq!(u[0], fe_from_hex(v[0]));
+            assert_eq!(u[1], fe_from_hex(v[1]));
+            assert_eq!(PublicKey::from(map_to_curve(&u[0])), point(v[2], v[3]));
+            assert_eq!(PublicKey::from(map_to_curve(&u[1])), point(v[4], v[5]));
+            assert_eq!(hash_to_curve(msg, RO_DST).unwrap(), point(v[6], v[7]));
+        }
+    }
+
+    #[test]
+    fn test_encode_to_curve_vectors() {
+        // RFC 9380 appendix J.8.2: u and P.
+        let vectors = [
+            [
+                "0137fcd23bc3da962e8808f97474d097a6c8aa2881fceef4514173635872cf3b",
+                "a4792346075feae77ac3b30026f99c1441b4ecf666ded19b7522cf65c4c55c5b",
+                "62c59e2a6aeed1b23be5883e833912b08ba06be7f57c0e9cdc663f31639ff3a7",
+            ],
+            [
+                "e03f894b4d7caf1a50d6aa45cac27412c8867a25489e32c5ddeb503229f63a2e",
+                "3f3b5842033fff837d504bb4ce2a372bfeadbdbd84a1d2b678b6e1d7ee426b9d",
+                "902910d1fef15d8ae2006fc84f2a5a7bda0e0407dc913062c3a493c4f5d876a5",
+            ],
+            [
+                "e7a6525ae7069ff43498f7f508b41c57f80563c1fe4283510b322446f32af41b",
+                "07644fa6281c694709f53bdd21bed94dab995671e4a8cd1904ec4aa50c59bfdf",
+                "c79f8d1dad79b6540426922f7fbc9579c3018dafeffcd4552b1626b506c21e7b",
+            ],
+            [
+                "d97cf3d176a2f26b9614a704d7d434739d194226a706c886c5c3c39806bc323c",
+                "b734f05e9b9709ab631d960fa26d669c4aeaea64ae62004b9d34f483aa9acc33",
+                "03fc8a4a5a78632e2eb4d8460d69ff33c1d72574b79a35e402e801f2d0b1d6ee",
+            ],
+            [
+                "a9ffbeee1d6e41ac33c248fb3364612ff591b502386c1bf6ac4aaf1ea51f8c3b",
+                "17d22b867658977b5002dbe8d0ee70a8cfddec3eec50fb93f36136070fd9fa6c",
+                "e9178ff02f4dab73480f8dd590328aea99856a7b6cc8e5a6cdf289ecc2a51718",
+            ],
+        ];
+        for (msg, v) in messages().iter().zip(vectors.iter()) {
+            assert_eq!(hash_to_field(msg, NU_DST, 1).unwrap()[0], fe_from_hex(v[0]));
+            assert_eq!(encode_to_curve(msg, NU_DST).unwrap(), point(v[1], v[2]));
+        }
+    }
+
+    #[test]
+    fn test_long_dst() {
+        let long_dst = [b'x'; 300];
+        let hashed = [&b"H2C-OVERSIZE-DST-"[..], &long_dst].concat().hash_digest();
+        assert_eq!(hash_to_curve(b"msg", &long_dst).unwrap(), hash_to_curve(b"msg", &hashed).unwrap());
+        assert_ne!(hash_to_curve(b"msg", RO_DST).unwrap(), encode_to_curve(b"msg", RO_DST).unwrap());
+    }
+}
@@ -0,0 +1,409 @@
+use crate::field::FieldElement;
+use crate::point::{Group, Point};
+use rug::{integer::Order, Integer};
+use std::ops::{Add, Mul, Sub};
+
+// The co-Z Montgomery ladder of Goundar, Joye and Miyaji ("Co-Z addition formulae and binary ladders on elliptic
+// curves", CHES 2010) on secp256k1, in constant time.
+// The ladder keeps R0 = jP and R1 = (j+1)P for the top bits j of the scalar, in Jacobian coordinates sharing the same Z.
+// Every bit is a conditional swap, an addition that also gives the difference (XYCZ-ADDC) and an addition (XYCZ-ADD),
+// whatever the bit is. The field arithmetic is on four 64 bit limbs without branches or indices depending on the values,
+// the swaps are masks, and adding n or 2n to the scalar sets its bit 256, so every scalar takes the same 256 steps.
+//
+// Scalars for which some jP = ±(j+1)P or jP is the infinity (k = 0 mod n, k = -1 mod n and a few others) hit the special
+// cases of the formulas, which leave Z = 0. Those are recomputed with rug's arithmetic, random scalars never get there.
+// The scalar comes in and the result goes out as rug integers, only those conversions use rug.
+// The field is secp256k1's: the points have to be on a curve y^2 = x^3 + b over it.
+
+// p = 2^256 - 2^32 - 977, least significant limb first.
+const P: [u64; 4] = [0xFFFF_FFFE_FFFF_FC2F, u64::MAX, u64::MAX, u64::MAX];
+// 2^256 mod p.
+const P_COMPLEMENT: u64 = 0x1_0000_03D1;
+// p - 2, the exponent of the inverse.
+const P_MINUS_2: [u64; 4] = [0xFFFF_FFFE_FFFF_FC2D, u64::MAX, u64::MAX, u64::MAX];
+// (p + 1) / 4, the exponent of the square root.
+const P_PLUS_1_DIV_4: [u64; 4] = [0xFFFF_FFFF_BFFF_FF0C, u64::MAX, u64::MAX, 0x3FFF_FFFF_FFFF_FFFF];
+// The order of the group.
+const N: [u64; 4] = [0xBFD2_5E8C_D036_4141, 0xBAAE_DCE6_AF48_A03B, 0xFFFF_FFFF_FFFF_FFFE, u64::MAX];
+
+// a + b and the carry.
+fn add_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut res = [0u64; 4];
+    let mut carry = 0u128;
+    for ((res, a), b) in res.iter_mut().zip(a).zip(b) {
+        let sum = u128::from(*a) + u128::from(*b) + carry;
+        *res = sum as u64;
+        carry = sum >> 64;
+    }
+    (res, carry as u64)
+}
+
+// a - b and the borrow.
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut res = [0u64; 4];
+    let mut borrow = 0u64;
+    for ((res, a), b) in res.iter_mut().zip(a).zip(b) {
+        let (diff, borrow1) = a.overflowing_sub(*b);
+        let (diff, borrow2) = diff.overflowing_sub(borrow);
+        *res = diff;
+        borrow = u64::from(borrow1 | borrow2);
+    }
+    (res, borrow)
+}
+
+// a if `mask` is 0, b if it's all ones.
+fn select(mask: u64, a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut res = [0u64; 4];
+    for ((res, a), b) in res.iter_mut().zip(a).zip(b) {
+        *res = a ^ (mask & (a ^ b));
+    }
+    res
+}
+
+// All ones if `bit` is 1, 0 if it's 0.
+fn mask(bit: u64) -> u64 {
+    0u64.wrapping_sub(bit)
+}
+
+// An element of secp256k1's field, always fully reduced.
+#[derive(Clone, Copy, Debug)]
+struct Fe([u64; 4]);
+
+impl Fe {
+    const ONE: Fe = Fe([1, 0, 0, 0]);
+
+    fn from_integer(num: &Integer) -> Fe {
+        let mut limbs = [0u64; 4];
+        num.write_digits(&mut limbs, Order::Lsf);
+        Fe(limbs)
+    }
+
+    fn to_field(self, modulo: &Integer) -> FieldElement {
+        FieldElement::new(Integer::from_digits(&self.0, Order::Lsf), modulo)
+    }
+
+    // The value of `limbs` + carry * 2^256 mod p, for values below 2p.
+    fn reduce_once(limbs: [u64; 4], carry: u64) -> Fe {
+        let (reduced, borrow) = sub_limbs(&limbs, &P);
+        Fe(select(mask(carry | (borrow ^ 1)), &limbs, &reduced))
+    }
+
+    // The 512 bit product mod p, folding the high half in twice with 2^256 = 2^32 + 977 mod p.
+    fn reduce_wide(wide: [u64; 8]) -> Fe {
+        let mut limbs = [0u64; 4];
+        let mut carry = 0u128;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let sum = u128::from(wide[i]) + u128::from(wide[i + 4]) * u128::from(P_COMPLEMENT) + carry;
+            *limb = sum as u64;
+            carry = sum >> 64;
+        }
+        let high = carry * u128::from(P_COMPLEMENT);
+        let (limbs, carry) = add_limbs(&limbs, &[high as u64, (high >> 64) as u64, 0, 0]);
+        // If that overflowed, the limbs are small and adding 2^256 mod p once more can't.
+        let (limbs, _) = add_limbs(&limbs, &[carry * P_COMPLEMENT, 0, 0, 0]);
+        Fe::reduce_once(limbs, 0)
+    }
+
+    fn square(self) -> Fe {
+        self * self
+    }
+
+    // Square and multiply, the exponent is public.
+    fn pow(self, exp: &[u64; 4]) -> Fe {
+        let mut res = Fe::ONE;
+        for i in (0..256).rev() {
+            res = res.square();
+            if (exp[i / 64] >> (i % 64)) & 1 == 1 {
+                res = res * self;
+            }
+        }
+        res
+    }
+
+    fn invert(self) -> Fe {
+        self.pow(&P_MINUS_2)
+    }
+
+    // A square root if there is one, p = 3 mod 4.
+    fn sqrt(self) -> Option<Fe> {
+        let root = self.pow(&P_PLUS_1_DIV_4);
+        if (root.square() - self).is_zero() {
+            Some(root)
+        } else {
+            None
+        }
+    }
+
+    fn is_zero(self) -> bool {
+        self.0.iter().fold(0, |acc, limb| acc | limb) == 0
+    }
+}
+
+impl Add for Fe {
+    type Output = Fe;
+    fn add(self, other: Fe) -> Fe {
+        let (sum, carry) = add_limbs(&self.0, &other.0);
+        Fe::reduce_once(sum, carry)
+    }
+}
+
+impl Sub for Fe {
+    type Output = Fe;
+    fn sub(self, other: Fe) -> Fe {
+        let (diff, borrow) = sub_limbs(&self.0, &other.0);
+        let (diff, _) = add_limbs(&diff, &select(mask(borrow), &[0; 4], &P));
+        Fe(diff)
+    }
+}
+
+impl Mul for Fe {
+    type Output = Fe;
+    fn mul(self, other: Fe) -> Fe {
+        let mut wide = [0u64; 8];
+        for (i, a) in self.0.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, b) in other.0.iter().enumerate() {
+                let product = u128::from(*a) * u128::from(*b) + u128::from(wide[i + j]) + carry;
+                wide[i + j] = product as u64;
+                carry = product >> 64;
+            }
+            wide[i + 4] = carry as u64;
+        }
+        Fe::reduce_wide(wide)
+    }
+}
+
+// A point in Jacobian coordinates (X, Y), its Z is the one shared by both points of the ladder.
+type CoZ = (Fe, Fe);
+
+// Swap a and b if `mask` is all ones.
+fn cswap(a: &mut CoZ, b: &mut CoZ, mask: u64) {
+    let (a_x, b_x) = (select(mask, &a.0 .0, &b.0 .0), select(mask, &b.0 .0, &a.0 .0));
+    let (a_y, b_y) = (select(mask, &a.1 .0, &b.1 .0), select(mask, &b.1 .0, &a.1 .0));
+    *a = (Fe(a_x), Fe(a_y));
+    *b = (Fe(b_x), Fe(b_y));
+}
+
+// XYCZ-IDBL: 2P and P with the same Z = 2y, from the affine P.
+fn double_affine(x: Fe, y: Fe) -> (CoZ, CoZ, Fe) {
+    let (xx, yy) = (x.square(), y.square());
+    let yyyy = yy.square();
+    let s = (x * yy) + (x * yy);
+    let s = s + s;
+    let m = xx + xx + xx;
+    let x2 = m.square() - s - s;
+    let yyyy8 = yyyy + yyyy;
+    let yyyy8 = yyyy8 + yyyy8;
+    let yyyy8 = yyyy8 + yyyy8;
+    let y2 = m * (s - x2) - yyyy8;
+    ((x2, y2), (s, yyyy8), y + y)
+}
+
+// XYCZ-ADDC: P + Q and P - Q, and the factor X2 - X1 their Z is the old one times.
+fn add_conjugate((x1, y1): CoZ, (x2, y2): CoZ) -> (CoZ, CoZ, Fe) {
+    let dx = x2 - x1;
+    let a = dx.square();
+    let (b, c) = (x1 * a, x2 * a);
+    let e = y1 * (c - b);
+    let dy = y2 - y1;
+    let x3 = dy.square() - b - c;
+    let y3 = dy * (b - x3) - e;
+    let sy = y1 + y2;
+    let x3_conj = sy.square() - b - c;
+    let y3_conj = sy * (x3_conj - b) - e;
+    ((x3, y3), (x3_conj, y3_conj), dx)
+}
+
+// XYCZ-ADD: P + Q and P, and the factor X2 - X1 their Z is the old one times.
+fn add((x1, y1): CoZ, (x2, y2): CoZ) -> (CoZ, CoZ, Fe) {
+    let dx = x2 - x1;
+    let a = dx.square();
+    let (b, c) = (x1 * a, x2 * a);
+    let e = y1 * (c - b);
+    let dy = y2 - y1;
+    let x3 = dy.square() - b - c;
+    let y3 = dy * (b - x3) - e;
+    ((x3, y3), (b, e), dx)
+}
+
+// k mod n plus n or 2n, whichever has bit 256 set, without that bit. `k` is below 2^256.
+fn pad_scalar(k: &Integer) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    k.write_digits(&mut limbs, Order::Lsf);
+    let (reduced, borrow) = sub_limbs(&limbs, &N);
+    let k = select(mask(borrow ^ 1), &limbs, &reduced);
+    let (plus_n, carry) = add_limbs(&k, &N);
+    let (plus_2n, _) = add_limbs(&plus_n, &N);
+    select(mask(carry), &plus_2n, &plus_n)
+}
+
+// kP and (k+1)P with their shared Z, which is 0 for the special cases.
+fn ladder(x: Fe, y: Fe, k: &Integer) -> (CoZ, CoZ, Fe) {
+    let k = pad_scalar(k);
+    let (mut r1, mut r0, mut z) = double_affine(x, y);
+    for i in (0..256).rev() {
+        // Put R_b first: (R_1-b, R_b) = ADDC(R_b, R_1-b), then (R_b, R_1-b) = ADD(R_1-b, R_b).
+        let swap = mask((k[i / 64] >> (i % 64)) & 1);
+        cswap(&mut r0, &mut r1, swap);
+        let (sum, diff, dz1) = add_conjugate(r0, r1);
+        let (sum, updated, dz2) = add(sum, diff);
+        r0 = sum;
+        r1 = updated;
+        z = z * dz1 * dz2;
+        cswap(&mut r0, &mut r1, swap);
+    }
+    (r0, r1, z)
+}
+
+// The affine (x, y) of a Jacobian point.
+fn to_affine((x, y): CoZ, z: Fe) -> (Fe, Fe) {
+    let z_inv = z.invert();
+    let z_inv2 = z_inv.square();
+    (x * z_inv2, y * z_inv2 * z_inv)
+}
+
+// The x-coordinate of kP, or None if it's the infinity or x isn't on the curve. k < 2^256.
+pub(crate) fn mul_x(x: &FieldElement, group: &Group, k: &Integer) -> Option<FieldElement> {
+    let fe_x = Fe::from_integer(&x.num);
+    // Either y works, x(-kP) = x(kP).
+    let fe_y = (fe_x.square() * fe_x + Fe::from_integer(&group.b)).sqrt()?;
+    let (r0, _, z) = ladder(fe_x, fe_y, k);
+    if z.is_zero() {
+        let point = Point { x: x.clone(), y: fe_y.to_field(&x.modulo), group: group.clone() };
+        let res = k * point;
+        return if res.is_on_infinity() { None } else { Some(res.x) };
+    }
+    Some(to_affine(r0, z).0.to_field(&x.modulo))
+}
+
+// kP, k < 2^256.
+pub(crate) fn mul_point(point: &Point, k: &Integer) -> Point {
+    if point.is_on_infinity() {
+        return point.clone();
+    }
+    let (r0, _, z) = ladder(Fe::from_integer(&point.x.num), Fe::from_integer(&point.y.num), k);
+    if z.is_zero() {
+        return k * point.clone();
+    }
+    let (x, y) = to_affine(r0, z);
+    let modulo = &point.x.modulo;
+    Point { x: x.to_field(modulo), y: y.to_field(modulo), group: point.group.clone() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secp256k1::get_context;
+
+    fn scalars() -> Vec<Integer> {
+        let order = &get_context().order;
+        let mut scalars: Vec<Integer> = vec![1.into(), 2.into(), 3.into(), Integer::from(order - 1), Integer::from(order - 2)];
+        let mut k = Integer::from(0x5eed);
+        for _ in 0..8 {
+            k = (k.clone() * &k + 0x1234_5678u32) % order;
+            scalars.push(k.clone());
+        }
+        scalars
+    }
+
+    #[test]
+    fn test_field() {
+        let modulo = &get_context().modulo;
+        let values: Vec<Integer> =
+            vec![0.into(), 1.into(), 2.into(), Integer::from(modulo - 1), Integer::from(modulo - 2), Integer::from(modulo >> 1)]
+                .into_iter()
+                .chain(scalars())
+                .collect();
+        for a in &values {
+            let (fa, a) = (Fe::from_integer(a), FieldElement::new(a.clone(), modulo));
+            for b in &values {
+                let (fb, b) = (Fe::from_integer(b), FieldElement::new(b.clone(), modulo));
+                assert_eq!((fa + fb).to_field(modulo), a.clone() + b.clone());
+                assert_eq!((fa - fb).to_field(modulo), a.clone() - b.clone());
+                assert_eq!((fa * fb).to_field(modulo), a.clone() * b.clone());
+            }
+            if !a.is_zero() {
+                assert_eq!((fa.invert() * fa).to_field(modulo), FieldElement::new(1, modulo));
+            }
+            assert_eq!(fa.sqrt().is_some(), a.is_zero() || a.is_square());
+            if let Some(root) = fa.sqrt() {
+                assert_eq!(root.square().to_field(modulo), a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_point() {
+        let secp = get_context();
+        let point = secp.mul_generator(&Integer::from(0xdead_beefu32));
+        for k in scalars() {
+            let expected = &k * point.clone();
+            assert_eq!(mul_point(&point, &k), expected);
+            assert_eq!(mul_x(&point.x, &point.group, &k), Some(expected.x));
+            let mut negated = point.clone();
+            negated.y.reflect();
+            assert_eq!(mul_point(&negated, &k), &k * negated);
+        }
+    }
+
+    #[test]
+    fn test_special_cases() {
+        let secp = get_context();
+        let point = secp.generator();
+        let (x, y) = (Fe::from_integer(&point.x.num), Fe::from_integer(&point.y.num));
+        // Random scalars take the constant time path, the special ones end with Z = 0 and are recomputed.
+        for k in scalars().iter().skip(5) {
+            assert!(!ladder(x, y, k).2.is_zero());
+        }
+        let order = &secp.order;
+        for k in [Integer::new(), order.clone(), Integer::from(order - 1)].iter() {
+            assert!(ladder(x, y, k).2.is_zero());
+            assert_eq!(mul_point(&point, k), k * point.clone());
+        }
+        // Not on the curve.
+        assert_eq!(mul_x(&FieldElement::new(0, &secp.modulo), &point.group, &Integer::from(2)), None);
+    }
+
+    #[test]
+    fn test_infinity() {
+        let secp = get_context();
+        let point = secp.generator();
+        assert!(mul_point(&point, &secp.order).is_on_infinity());
+        assert_eq!(mul_x(&point.x, &point.group, &secp.order), None);
+        assert_eq!(mul_x(&point.x, &point.group, &Integer::new()), None);
+        assert!(mul_point(&point.gen_zero(), &Integer::from(5)).is_on_infinity());
+    }
+}
+
+#[cfg(all(test, feature = "nightly"))]
+mod benches {
+    extern crate test;
+    use self::test::{black_box, Bencher};
+    use super::*;
+    use crate::secp256k1::get_context;
+
+    fn scalar() -> Integer {
+        Integer::from_str_radix("c90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22", 16).unwrap()
+    }
+
+    #[bench]
+    pub fn bench_ladder_x(bh: &mut Bencher) {
+        let point = get_context().generator();
+        let k = scalar();
+        bh.iter(|| black_box(mul_x(&point.x, &point.group, &k)));
+    }
+
+    #[bench]
+    pub fn bench_ladder_point(bh: &mut Bencher) {
+        let point = get_context().generator();
+        let k = scalar();
+        bh.iter(|| black_box(mul_point(&point, &k)));
+    }
+
+    #[bench]
+    pub fn bench_point_mul(bh: &mut Bencher) {
+        let point = get_context().generator();
+        let k = scalar();
+        bh.iter(|| black_box(&k * point.clone()));
+    }
+}
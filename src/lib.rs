@@ -1,16 +1,36 @@
 #![cfg_attr(feature = "nightly", feature(test))]
 
+mod batch;
 mod field;
 mod hash;
 pub mod internal;
 mod jacobi;
+mod ladder;
 mod point;
 mod secp256k1;
+mod taproot;
 //mod u256;
 mod ffi;
 
 //extra added
-mod musig;
+pub mod musig;
+pub mod frost;
+pub mod roast;
+pub mod shamir;
+pub mod ecdsa_2p;
+pub mod schnorr_adaptor;
+pub mod ecdsa_adaptor;
+mod paillier;
+mod chacha20;
+pub mod dleq;
+pub mod transcript;
+pub mod sigma;
+pub mod half_agg;
+pub mod blind_schnorr;
+pub mod bdhke;
+pub mod hash_to_curve;
+pub mod ellswift;
+pub mod bip324;
 mod utility;
 mod address;
 
@@ -24,7 +44,12 @@ mod opcodes;
 #[cfg(test)]
 mod test_vectors;
 
-pub use crate::secp256k1::{PrivateKey, PublicKey, SchnorrSignature, Signature};
+pub use crate::batch::verify_many;
+pub use crate::secp256k1::{PrivateKey, PublicKey, Scalar, SchnorrSignature, Signature};
+pub use crate::script::{Builder as ScriptBuilder, Script};
+pub use crate::taproot::{
+    tap_branch_hash, tap_tweak_hash, ControlBlock, TapLeaf, TaprootBuilder, TaprootSpendInfo, TAPROOT_LEAF_TAPSCRIPT,
+};
 pub use hash::*;
 
 pub use crate::ffi::{ecdsa::*, schnorr::*, *};
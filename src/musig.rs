@@ -1,168 +1,775 @@
-use crate::hash::HashTrait;
-use crate::secp256k1::{get_context, PrivateKey, PublicKey, Scalar, SchnorrSignature};
+use crate::point::Point;
+use crate::secp256k1::{bip340_challenge, get_context, PrivateKey, PublicKey, Scalar, SchnorrSignature};
+use crate::taproot::tap_tweak_hash;
+use crate::utility::{int_from_bytes, int_to_bytes, tagged_hash, zeroize};
 use rug::{integer::Order, Integer};
-use std::convert::TryInto;
 
-#[allow(dead_code)]
-pub fn get_agg_musig_pubkey(pubkeys: &[PublicKey]) -> (Vec<(Scalar, PublicKey)>, PublicKey) {
-    //Collect the x-coordinate of pubkeys into a vector
-    let mut collection: Vec<[u8; 32]> = Vec::new();
-    for pubkey in pubkeys {
-        collection.push(pubkey.clone().compressed()[1..].try_into().unwrap());
+// BIP327 MuSig2.
+// Every signer needs its own session per message: nonces are generated with `nonce_gen`, exchanged and aggregated
+// with `nonce_agg`, then each signer creates a `Session` to produce its partial signature.
+
+// Sort the keys by their compressed serialization (BIP327 `KeySort`), so the aggregate key doesn't depend on the signers order.
+pub fn sort_pubkeys(pubkeys: &[PublicKey]) -> Vec<PublicKey> {
+    let mut sorted = pubkeys.to_vec();
+    sorted.sort_by_key(PublicKey::compressed);
+    sorted
+}
+
+#[derive(Clone, Debug)]
+pub struct KeyAggContext {
+    pubkeys: Vec<PublicKey>,
+    // hash_KeyAgg list(pk_1 || ... || pk_u)
+    list_hash: [u8; 32],
+    // The first key that differs from the first one, it gets the coefficient 1 to save a multiplication.
+    second_key: Option<PublicKey>,
+    q: PublicKey,
+    // The tweaked key is Q = gacc*Q_0 + tacc*G, where Q_0 is the untweaked aggregate key.
+    gacc: Integer,
+    tacc: Integer,
+}
+
+impl KeyAggContext {
+    // BIP327 `KeyAgg`, the order of the keys matters. Use `new_sorted` to make it independent of the order.
+    pub fn new(pubkeys: &[PublicKey]) -> Result<KeyAggContext, &'static str> {
+        if pubkeys.is_empty() {
+            return Err("There are no public keys to aggregate");
+        }
+        let serialized: Vec<u8> = pubkeys.iter().flat_map(|pk| pk.compressed().to_vec()).collect();
+        let list_hash = tagged_hash(b"KeyAgg list", &serialized);
+        let second_key = pubkeys.iter().find(|pk| *pk != &pubkeys[0]).cloned();
+        let mut ctx = KeyAggContext {
+            pubkeys: pubkeys.to_vec(),
+            list_hash,
+            second_key,
+            q: PublicKey::zero_pubkey(),
+            gacc: Integer::from(1),
+            tacc: Integer::new(),
+        };
+
+        let q =
+            pubkeys.iter().fold(Point::from(PublicKey::zero_pubkey()), |q, pk| q + ctx.key_agg_coeff(pk) * Point::from(pk.clone()));
+        if q.is_on_infinity() {
+            return Err("The aggregate public key is the point at infinity");
+        }
+        ctx.q = PublicKey::from(q);
+        Ok(ctx)
+    }
+
+    pub fn new_sorted(pubkeys: &[PublicKey]) -> Result<KeyAggContext, &'static str> {
+        Self::new(&sort_pubkeys(pubkeys))
+    }
+
+    pub fn pubkeys(&self) -> &[PublicKey] {
+        &self.pubkeys
+    }
+
+    // Add a plain tweak, Q + t*G, e.g. for BIP32 derivation of the aggregate key (BIP327 `ApplyTweak`).
+    pub fn plain_tweak_add(&self, tweak: &Scalar) -> Result<KeyAggContext, &'static str> {
+        self.apply_tweak(tweak, false)
+    }
+
+    // Add an x-only tweak, lift_x(Q.x) + t*G, like a taproot commitment.
+    pub fn xonly_tweak_add(&self, tweak: &Scalar) -> Result<KeyAggContext, &'static str> {
+        self.apply_tweak(tweak, true)
+    }
+
+    // Tweak the aggregate key into a BIP341 taproot output key, the aggregate key is the internal key.
+    pub fn tap_tweak(&self, merkle_root: Option<&[u8; 32]>) -> Result<KeyAggContext, &'static str> {
+        self.xonly_tweak_add(&tap_tweak_hash(&self.q, merkle_root))
+    }
+
+    fn apply_tweak(&self, tweak: &Scalar, is_xonly: bool) -> Result<KeyAggContext, &'static str> {
+        let order = &get_context().order;
+        let t = int_from_bytes(&tweak.0);
+        if t >= *order {
+            return Err("The tweak overflows the curve order");
+        }
+        let mut ctx = self.clone();
+        // Tweaking the x-only key means tweaking -Q if Q has an odd y.
+        if is_xonly && !self.q.has_even_y() {
+            ctx.q = ctx.q.negate();
+            ctx.gacc = order - ctx.gacc;
+            ctx.tacc = (order - ctx.tacc) % order;
+        }
+        let q = Point::from(ctx.q) + get_context().mul_generator(&t);
+        if q.is_on_infinity() {
+            return Err("The tweaked aggregate public key is the point at infinity");
+        }
+        ctx.q = PublicKey::from(q);
+        ctx.tacc = (t + ctx.tacc) % order;
+        Ok(ctx)
+    }
+
+    // The aggregate key Q with its parity.
+    pub fn agg_pubkey(&self) -> &PublicKey {
+        &self.q
+    }
+
+    // The BIP340 key the final signature verifies under.
+    pub fn x_only_agg_pubkey(&self) -> [u8; 32] {
+        self.q.x_only()
+    }
+
+    // a_i = int(hash_KeyAgg coefficient(L || pk_i)) mod n, or 1 for the second key.
+    pub(crate) fn key_agg_coeff(&self, pubkey: &PublicKey) -> Integer {
+        if Some(pubkey) == self.second_key.as_ref() {
+            return Integer::from(1);
+        }
+        let hash = tagged_hash(b"KeyAgg coefficient", &[&self.list_hash[..], &pubkey.compressed()].concat());
+        Integer::from_digits(&hash, Order::MsfBe) % &get_context().order
     }
+}
 
-    //sort the vector
-    collection.sort();
+// The two public nonce points R1 || R2 a signer sends to the others.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PubNonce {
+    r1: PublicKey,
+    r2: PublicKey,
+}
 
-    // unbag the vector into a Vec<u8>
-    // preperation for hashing
-    let col_array: Vec<u8> = collection.iter().fold(Vec::new(), |agg, x| [agg, x.clone().to_vec()].concat());
+impl PubNonce {
+    pub fn serialize(&self) -> [u8; 66] {
+        let mut res = [0u8; 66];
+        res[..33].copy_from_slice(&self.r1.compressed());
+        res[33..].copy_from_slice(&self.r2.compressed());
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<PubNonce, &'static str> {
+        if ser.len() != 66 {
+            return Err("A public nonce should be 66 bytes");
+        }
+        Ok(PubNonce { r1: PublicKey::from_compressed(&ser[..33])?, r2: PublicKey::from_compressed(&ser[33..])? })
+    }
+}
 
-    // hash it
-    let c_all = col_array.hash_digest();
+// The sum of all the public nonces. Unlike public nonces the points can be infinity, serialized as 33 zero bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggNonce {
+    r1: PublicKey,
+    r2: PublicKey,
+}
 
-    // Construct the challenge tupple
-    let mut challenge = Vec::new();
-    for pubkey in pubkeys {
-        let c_i = [c_all, pubkey.clone().compressed()[1..].try_into().unwrap()].concat().hash_digest();
-        challenge.push((Scalar::new(&c_i), pubkey.clone()));
+impl AggNonce {
+    pub fn serialize(&self) -> [u8; 66] {
+        let mut res = [0u8; 66];
+        res[..33].copy_from_slice(&cbytes_ext(&self.r1));
+        res[33..].copy_from_slice(&cbytes_ext(&self.r2));
+        res
     }
 
-    // Crunch out aggreagate pubkey = sum (pubkey_i * c_i)
-    let agg_pubkey = challenge.iter().fold(PublicKey::zero_pubkey(), |agg, x| agg + (&x.1 * &x.0));
+    pub fn parse(ser: &[u8]) -> Result<AggNonce, &'static str> {
+        if ser.len() != 66 {
+            return Err("An aggregate nonce should be 66 bytes");
+        }
+        Ok(AggNonce { r1: cpoint_ext(&ser[..33])?, r2: cpoint_ext(&ser[33..])? })
+    }
+}
 
-    // Return (Challenge tupple, Aggregate Pubkey)
-    (challenge, agg_pubkey)
+fn cbytes_ext(pubkey: &PublicKey) -> [u8; 33] {
+    if Point::from(pubkey.clone()).is_on_infinity() {
+        [0u8; 33]
+    } else {
+        pubkey.compressed()
+    }
 }
 
-#[allow(dead_code)]
-pub fn agg_schnorr_nonces(nonce_points: &[PublicKey]) -> (PublicKey, bool) {
-    let sum: PublicKey = nonce_points.iter().sum();
-    if !sum.is_square_y() {
-        (sum.negate(), true)
+fn cpoint_ext(ser: &[u8]) -> Result<PublicKey, &'static str> {
+    if ser.iter().all(|b| *b == 0) {
+        Ok(PublicKey::zero_pubkey())
     } else {
-        (sum, false)
+        PublicKey::from_compressed(ser)
+    }
+}
+
+// The secret nonce k1 || k2 || pk. Signing twice with the same nonce leaks the private key, so it isn't `Clone`,
+// `partial_sign` consumes it, and the memory is zeroed when it's dropped.
+pub struct SecNonce([u8; 97]);
+
+impl SecNonce {
+    // Only for signers that have to persist the nonce between the rounds.
+    // Restoring the same bytes twice reuses the nonce.
+    pub fn dangerous_from_bytes(bytes: [u8; 97]) -> SecNonce {
+        SecNonce(bytes)
+    }
+
+    pub fn dangerous_into_bytes(self) -> [u8; 97] {
+        self.0
+    }
+}
+
+impl Drop for SecNonce {
+    fn drop(&mut self) {
+        zeroize(&mut self.0);
+    }
+}
+
+// BIP327 `NonceGen`. `rand` must be fresh randomness for every call,
+// the other (optional) inputs only add defense in depth in case the randomness is bad.
+pub fn nonce_gen(
+    rand: &[u8; 32],
+    sk: Option<&PrivateKey>,
+    pk: &PublicKey,
+    agg_pubkey: Option<&[u8; 32]>,
+    msg: Option<&[u8]>,
+    extra_in: Option<&[u8]>,
+) -> (SecNonce, PubNonce) {
+    let secp = get_context();
+    let mut rand = *rand;
+    if let Some(sk) = sk {
+        let aux = tagged_hash(b"MuSig/aux", &rand);
+        rand = sk.serialize();
+        rand.iter_mut().zip(aux.iter()).for_each(|(r, a)| *r ^= a);
     }
+    let pk = pk.compressed();
+    let agg_pubkey = agg_pubkey.map_or(&[][..], |agg_pubkey| &agg_pubkey[..]);
+    let msg_prefixed = match msg {
+        None => vec![0],
+        Some(msg) => [&[1], &(msg.len() as u64).to_be_bytes()[..], msg].concat(),
+    };
+    let extra_in = extra_in.unwrap_or(&[]);
+
+    let nonce = |i: u8| {
+        let data = [
+            &rand[..],
+            &[pk.len() as u8],
+            &pk,
+            &[agg_pubkey.len() as u8],
+            agg_pubkey,
+            &msg_prefixed,
+            &(extra_in.len() as u32).to_be_bytes(),
+            extra_in,
+            &[i],
+        ]
+        .concat();
+        int_from_bytes(&tagged_hash(b"MuSig/nonce", &data)) % &secp.order
+    };
+    nonce_pair(nonce(0), nonce(1), &pk)
 }
 
-#[allow(dead_code)]
-pub fn sign_musig(privkey: &PrivateKey, nonce: &PrivateKey, r_agg: &PublicKey, pub_agg: &PublicKey, msg: &[u8; 32]) -> [u8; 32] {
+fn nonce_pair(k1: Integer, k2: Integer, pk: &[u8; 33]) -> (SecNonce, PubNonce) {
     let secp = get_context();
-    assert!(Integer::from_digits(&privkey.serialize(), Order::MsfBe) < secp.order);
-    assert_ne!(Integer::from_digits(&privkey.serialize(), Order::MsfBe), 0);
-    assert!(r_agg.is_square_y());
+    let mut secnonce = SecNonce([0u8; 97]);
+    secnonce.0[..32].copy_from_slice(&int_to_bytes(&k1));
+    secnonce.0[32..64].copy_from_slice(&int_to_bytes(&k2));
+    secnonce.0[64..].copy_from_slice(pk);
+    let pubnonce = PubNonce { r1: secp.mul_generator(&k1).into(), r2: secp.mul_generator(&k2).into() };
+    (secnonce, pubnonce)
+}
+
+// BIP327 `NonceAgg`, can be done by any of the signers or by an untrusted coordinator.
+pub fn nonce_agg(pubnonces: &[PubNonce]) -> AggNonce {
+    AggNonce { r1: pubnonces.iter().map(|nonce| &nonce.r1).sum(), r2: pubnonces.iter().map(|nonce| &nonce.r2).sum() }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartialSignature(Integer);
+
+impl PartialSignature {
+    pub fn serialize(&self) -> [u8; 32] {
+        int_to_bytes(&self.0)
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<PartialSignature, &'static str> {
+        if ser.len() != 32 {
+            return Err("A partial signature should be 32 bytes");
+        }
+        let s = int_from_bytes(ser);
+        if s >= get_context().order {
+            return Err("The partial signature overflows the curve order");
+        }
+        Ok(PartialSignature(s))
+    }
+}
+
+// The values every signer derives from the aggregate nonce, the key aggregation and the message (BIP327 `GetSessionValues`).
+#[derive(Clone, Debug)]
+pub struct Session {
+    key_agg: KeyAggContext,
+    // The nonce coefficient.
+    b: Integer,
+    // The final nonce R = R1 + b*R2.
+    r: PublicKey,
+    // The BIP340 challenge.
+    e: Integer,
+}
+
+impl Session {
+    pub fn new(key_agg: &KeyAggContext, aggnonce: &AggNonce, msg: &[u8]) -> Session {
+        let secp = get_context();
+        let q = key_agg.x_only_agg_pubkey();
+        let b = tagged_hash(b"MuSig/noncecoef", &[&aggnonce.serialize()[..], &q, msg].concat());
+        let b = int_from_bytes(&b) % &secp.order;
+
+        let r = Point::from(aggnonce.r1.clone()) + &b * Point::from(aggnonce.r2.clone());
+        // Nobody knows the discrete log of R if it's infinity, so G is as good as any point.
+        let r = if r.is_on_infinity() { secp.generator() } else { r };
+        let e = bip340_challenge(&r.x.clone().serialize_num(), &q, msg).num;
+        Session { key_agg: key_agg.clone(), b, r: r.into(), e }
+    }
+
+    // BIP327 `Sign`. The secret nonce is consumed (and zeroed) even if signing fails.
+    pub fn partial_sign(&self, secnonce: SecNonce, sk: &PrivateKey) -> Result<PartialSignature, &'static str> {
+        let order = &get_context().order;
+        let k1 = int_from_bytes(&secnonce.0[..32]);
+        let k2 = int_from_bytes(&secnonce.0[32..64]);
+        if k1 == 0 || k1 >= *order || k2 == 0 || k2 >= *order {
+            return Err("The secret nonce is invalid or was already used");
+        }
+        if sk.scalar == 0 || sk.scalar >= *order {
+            return Err("The private key is not in the range [1, n-1]");
+        }
+        let pk = sk.generate_pubkey();
+        if pk.compressed()[..] != secnonce.0[64..] {
+            return Err("The secret nonce was generated for another public key");
+        }
+        if !self.key_agg.pubkeys.contains(&pk) {
+            return Err("The public key is not part of the aggregate key");
+        }
 
-    let data = [r_agg.compressed()[1..].to_vec(), pub_agg.compressed()[..].to_vec(), msg[..].to_vec()].concat();
+        let (k1, k2) = if self.r.has_even_y() { (k1, k2) } else { (order - k1, order - k2) };
+        let d = Integer::from(&self.key_agg.gacc * &sk.scalar) % order;
+        let d = if self.key_agg.q.has_even_y() { d } else { order - d };
+        let a = self.key_agg.key_agg_coeff(&pk);
+        let s = (k1 + &self.b * k2 + &self.e * a * d) % order;
+        Ok(PartialSignature(s))
+    }
 
-    let e = Scalar::new(&data.hash_digest());
+    // BIP327 `PartialSigVerifyInternal`: s*G == R1 + b*R2 + e*a*P, with R and P negated to match the parity of the final nonce and key.
+    pub fn partial_sig_verify(&self, psig: &PartialSignature, pubnonce: &PubNonce, pk: &PublicKey) -> bool {
+        let secp = get_context();
+        if !self.key_agg.pubkeys.contains(pk) {
+            return false;
+        }
+        let re = Point::from(pubnonce.r1.clone()) + &self.b * Point::from(pubnonce.r2.clone());
+        let re = if self.r.has_even_y() || re.is_on_infinity() { re } else { PublicKey::from(re).negate().into() };
+        let mut ea = (&self.e * self.key_agg.key_agg_coeff(pk) * &self.key_agg.gacc) % &secp.order;
+        if !self.key_agg.q.has_even_y() {
+            ea = &secp.order - ea;
+        }
+        secp.mul_generator(&psig.0) == re + ea * Point::from(pk.clone())
+    }
+
+    // BIP327 `PartialSigAgg`, the result is a BIP340 signature for the x-only (tweaked) aggregate key.
+    // The signers only sign for the untweaked key, the tweak is added here as e*tacc.
+    pub fn partial_sig_agg(&self, psigs: &[PartialSignature]) -> SchnorrSignature {
+        let order = &get_context().order;
+        let mut et = Integer::from(&self.e * &self.key_agg.tacc) % order;
+        if !self.key_agg.q.has_even_y() {
+            et = (order - et) % order;
+        }
+        let s = psigs.iter().fold(et, |s, psig| (s + &psig.0) % order);
+        SchnorrSignature::new(&self.r.x_only(), &int_to_bytes(&s))
+    }
 
-    let s = nonce + &(privkey * &e);
+    // Like `partial_sig_agg` but first verifies every partial signature against the signer's public nonce and key,
+    // `psigs[i]` has to be from the signer of `pubnonces[i]` and `pubkeys[i]`.
+    // On failure names all the faulty signers so they can be excluded from the next attempt.
+    pub fn partial_sig_agg_verified(
+        &self,
+        psigs: &[PartialSignature],
+        pubnonces: &[PubNonce],
+        pubkeys: &[PublicKey],
+    ) -> Result<SchnorrSignature, PartialSigAggError> {
+        if psigs.len() != pubnonces.len() || psigs.len() != pubkeys.len() {
+            return Err(PartialSigAggError::LengthMismatch);
+        }
+        let faulty: Vec<_> = (0..psigs.len()).filter(|&i| !self.partial_sig_verify(&psigs[i], &pubnonces[i], &pubkeys[i])).collect();
+        if faulty.is_empty() {
+            Ok(self.partial_sig_agg(psigs))
+        } else {
+            Err(PartialSigAggError::InvalidPartialSignatures(faulty))
+        }
+    }
+}
 
-    s.serialize()
+// Why `partial_sig_agg_verified` failed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PartialSigAggError {
+    // There isn't exactly one public nonce and key for every partial signature.
+    LengthMismatch,
+    // The signers (by index) whose partial signature is invalid.
+    InvalidPartialSignatures(Vec<usize>),
 }
 
-#[allow(dead_code)]
-pub fn aggregate_musig_signatures(sigs: &[[u8; 32]], r_agg: &PublicKey) -> SchnorrSignature {
-    let integers: Vec<Integer> = sigs.iter().map(|sig| Integer::from_digits(sig, Order::MsfBe)).collect();
-    let sum: Integer = integers.iter().fold(Integer::new(), |agg, x| (agg + x) % &get_context().order);
-    let mut s = [0u8; 32];
-    s[..].copy_from_slice(&sum.to_digits(Order::MsfBe));
-    let mut r = [0u8; 32];
-    r[..].copy_from_slice(&r_agg.compressed()[1..]);
+// BIP327 `DeterministicSign`, for a last signer that already received every other public nonce and doesn't want to keep any state.
+// The nonce is derived from the private key and everything that's being signed, `rand` is optional but recommended.
+// The signer must never sign with the same private key using a non deterministic nonce for the same session.
+pub fn deterministic_sign(
+    sk: &PrivateKey,
+    aggothernonce: &AggNonce,
+    key_agg: &KeyAggContext,
+    msg: &[u8],
+    rand: Option<&[u8; 32]>,
+) -> Result<(PubNonce, PartialSignature), &'static str> {
+    let order = &get_context().order;
+    let mut sk_bytes = sk.serialize();
+    if let Some(rand) = rand {
+        let aux = tagged_hash(b"MuSig/aux", rand);
+        sk_bytes.iter_mut().zip(aux.iter()).for_each(|(byte, aux)| *byte ^= aux);
+    }
+    let msg_len = (msg.len() as u64).to_be_bytes();
+    let nonce = |i: u8| {
+        let data = [&sk_bytes[..], &aggothernonce.serialize(), &key_agg.x_only_agg_pubkey(), &msg_len, msg, &[i]].concat();
+        int_from_bytes(&tagged_hash(b"MuSig/deterministic/nonce", &data)) % order
+    };
+    let (k1, k2) = (nonce(0), nonce(1));
+    if k1 == 0 || k2 == 0 {
+        return Err("The deterministic nonce is zero");
+    }
 
-    SchnorrSignature::new(&r, &s)
+    let (secnonce, pubnonce) = nonce_pair(k1, k2, &sk.generate_pubkey().compressed());
+    let aggnonce = AggNonce {
+        r1: (Point::from(aggothernonce.r1.clone()) + Point::from(pubnonce.r1.clone())).into(),
+        r2: (Point::from(aggothernonce.r2.clone()) + Point::from(pubnonce.r2.clone())).into(),
+    };
+    let psig = Session::new(key_agg, &aggnonce, msg).partial_sign(secnonce, sk)?;
+    Ok((pubnonce, psig))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utility::bytes_to_hex;
+    use crate::test_vectors::*;
+    use crate::utility::hex_to_bytes;
+    use std::convert::TryInto;
 
-    #[test]
-    fn test_get_agg_musig_pubkey() {
-        //make Private Key from hash digest
-        let privkey_1 = PrivateKey::from_serialized(&b"key0".hash_digest()[..]);
-        let privkey_2 = PrivateKey::from_serialized(&b"key1".hash_digest()[..]);
-        let privkey_3 = PrivateKey::from_serialized(&b"key2".hash_digest()[..]);
+    fn pubkey(hex: &str) -> Result<PublicKey, &'static str> {
+        PublicKey::from_compressed(&hex_to_bytes(hex))
+    }
 
-        //make corresponding pubkeys
-        let pubkey_1 = privkey_1.generate_pubkey();
-        let pubkey_2 = privkey_2.generate_pubkey();
-        let pubkey_3 = privkey_3.generate_pubkey();
+    fn pubkeys(all: &[&str], indices: &[usize]) -> Result<Vec<PublicKey>, &'static str> {
+        indices.iter().map(|i| pubkey(all[*i])).collect()
+    }
 
-        let (challenges, agg_pubkey) = get_agg_musig_pubkey(&[pubkey_1, pubkey_2, pubkey_3]);
+    fn pubnonces(indices: &[usize]) -> Result<Vec<PubNonce>, &'static str> {
+        indices.iter().map(|i| PubNonce::parse(&hex_to_bytes(MUSIG_SIGN_PUBNONCES[*i]))).collect()
+    }
 
-        // Tweak Priv key as per challenges
-        let privkey_1_c = privkey_1 * &challenges[0].0;
-        let privkey_2_c = privkey_2 * &challenges[1].0;
-        let privkey_3_c = privkey_3 * &challenges[2].0;
+    fn secnonce(hex: &str) -> SecNonce {
+        SecNonce::dangerous_from_bytes(hex_to_bytes(hex)[..].try_into().unwrap())
+    }
 
-        // assert aggregate pubkeys as tweaks
-        assert_eq!(
-            "02eeeea7d79f3ecde08d2a3c59f40eb3adcac9defb77d3b92053e5df95165139cd".to_uppercase(),
-            bytes_to_hex(&agg_pubkey.compressed())
-        );
+    fn apply_tweaks(ctx: KeyAggContext, all: &[&str], tweaks: &[(usize, bool)]) -> Result<KeyAggContext, &'static str> {
+        tweaks.iter().try_fold(ctx, |ctx, (i, is_xonly)| {
+            let tweak = Scalar::new(&hex_to_bytes(all[*i]));
+            if *is_xonly {
+                ctx.xonly_tweak_add(&tweak)
+            } else {
+                ctx.plain_tweak_add(&tweak)
+            }
+        })
+    }
 
-        assert_eq!("E7840B6872AF61DCA5EDB4B1334958D1FAB3D1851F376D0C4252881404AEC711", bytes_to_hex(&privkey_1_c.serialize()));
+    #[test]
+    fn test_key_sort() {
+        let sorted = sort_pubkeys(&pubkeys(&MUSIG_KEY_SORT_PUBKEYS, &[0, 1, 2, 3, 4, 5]).unwrap());
+        assert_eq!(sorted, pubkeys(&MUSIG_KEY_SORT_SORTED, &[0, 1, 2, 3, 4, 5]).unwrap());
+    }
 
-        assert_eq!("90EEBF5AFFD698DFB4B938B5FAB1943287F867AB31B07D18FCA33FF7D984BADC", bytes_to_hex(&privkey_2_c.serialize()));
+    #[test]
+    fn test_key_agg_vectors() {
+        for (indices, expected) in &MUSIG_KEY_AGG_VALID {
+            let ctx = KeyAggContext::new(&pubkeys(&MUSIG_KEY_AGG_PUBKEYS, indices).unwrap()).unwrap();
+            assert_eq!(ctx.x_only_agg_pubkey()[..], hex_to_bytes(expected)[..]);
+        }
+        for indices in &MUSIG_KEY_AGG_INVALID_PUBKEY {
+            assert!(pubkeys(&MUSIG_KEY_AGG_PUBKEYS, indices).is_err());
+        }
+        assert!(KeyAggContext::new(&[]).is_err());
+    }
 
-        assert_eq!("EC8F1CDE74C3151170CAEB9C2A25FF69F2EF25EF89AD07C195FA1F44DDB6C290", bytes_to_hex(&privkey_3_c.serialize()));
+    #[test]
+    fn test_key_agg_invalid_tweaks() {
+        for (indices, tweaks) in &MUSIG_KEY_AGG_INVALID_TWEAK {
+            let ctx = KeyAggContext::new(&pubkeys(&MUSIG_KEY_AGG_PUBKEYS, indices).unwrap()).unwrap();
+            assert!(apply_tweaks(ctx, &MUSIG_KEY_AGG_TWEAKS, tweaks).is_err());
+        }
+    }
 
-        //make nonce keys
-        let mut key_1 = PrivateKey::new(Integer::from(101));
-        let mut key_2 = PrivateKey::new(Integer::from(222));
-        let mut key_3 = PrivateKey::new(Integer::from(333));
+    #[test]
+    fn test_tweak_vectors() {
+        let sk = PrivateKey::from_serialized(&hex_to_bytes(MUSIG_SIGN_SK));
+        let aggnonce = AggNonce::parse(&hex_to_bytes(MUSIG_TWEAK_AGGNONCE)).unwrap();
+        let msg = hex_to_bytes(MUSIG_TWEAK_MSG);
+        for (key_indices, nonce_indices, tweaks, signer, expected) in &MUSIG_TWEAK_VALID {
+            let ctx = KeyAggContext::new(&pubkeys(&MUSIG_TWEAK_PUBKEYS, key_indices).unwrap()).unwrap();
+            let ctx = apply_tweaks(ctx, &MUSIG_TWEAK_TWEAKS, tweaks).unwrap();
+            let nonces: Vec<_> =
+                nonce_indices.iter().map(|i| PubNonce::parse(&hex_to_bytes(MUSIG_TWEAK_PUBNONCES[*i])).unwrap()).collect();
+            assert_eq!(nonce_agg(&nonces), aggnonce);
+
+            let session = Session::new(&ctx, &aggnonce, &msg);
+            let psig = session.partial_sign(secnonce(MUSIG_TWEAK_SECNONCE), &sk).unwrap();
+            assert_eq!(psig.serialize()[..], hex_to_bytes(expected)[..]);
+            assert!(session.partial_sig_verify(&psig, &nonces[*signer], &ctx.pubkeys()[*signer]));
+        }
+        let ctx = KeyAggContext::new(&pubkeys(&MUSIG_TWEAK_PUBKEYS, &[1, 2, 0]).unwrap()).unwrap();
+        assert!(apply_tweaks(ctx, &MUSIG_TWEAK_TWEAKS, &[(4, false)]).is_err());
+    }
 
-    
-        // Generate Nonce Points
-        let r1 = key_1.generate_pubkey();
-        let r2 = key_2.generate_pubkey();
-        let r3 = key_3.generate_pubkey();
-        
-        /*
-        let R1_digest = R1.clone().compressed().hash_digest();
-        let R2_digest = R2.clone().compressed().hash_digest();
-        let R3_digest = R3.clone().compressed().hash_digest();
+    #[test]
+    fn test_second_key_coefficient() {
+        let keys = pubkeys(&MUSIG_KEY_AGG_PUBKEYS, &[0, 0, 1, 2]).unwrap();
+        let ctx = KeyAggContext::new(&keys).unwrap();
+        assert_eq!(ctx.key_agg_coeff(&keys[2]), 1);
+        assert_ne!(ctx.key_agg_coeff(&keys[0]), 1);
+        assert_eq!(KeyAggContext::new_sorted(&keys).unwrap().pubkeys(), &sort_pubkeys(&keys)[..]);
+    }
 
-        assert_eq!("aa5d4a40c1843456534d75f7246b9cbf0f825a36113102e76193f80e6c652c72".to_uppercase(),
-                   bytes_to_hex(&R1_digest));
+    #[test]
+    fn test_nonce_gen_vectors() {
+        for vector in &MUSIG_NONCE_GEN_VECTORS {
+            let rand: [u8; 32] = hex_to_bytes(vector.rand)[..].try_into().unwrap();
+            let sk = vector.sk.map(|sk| PrivateKey::from_serialized(&hex_to_bytes(sk)));
+            let agg_pubkey: Option<[u8; 32]> = vector.agg_pubkey.map(|agg_pubkey| hex_to_bytes(agg_pubkey)[..].try_into().unwrap());
+            let msg = vector.msg.map(hex_to_bytes);
+            let extra_in = vector.extra_in.map(hex_to_bytes);
+
+            let (secnonce, pubnonce) =
+                nonce_gen(&rand, sk.as_ref(), &pubkey(vector.pk).unwrap(), agg_pubkey.as_ref(), msg.as_deref(), extra_in.as_deref());
+            assert_eq!(secnonce.dangerous_into_bytes()[..], hex_to_bytes(vector.secnonce)[..]);
+            assert_eq!(pubnonce.serialize()[..], hex_to_bytes(vector.pubnonce)[..]);
+        }
+    }
 
-        assert_eq!("6a78f2c6ca3bf1364f0575a02ef334d176df2051084c0e3062ec2af79b5b406a".to_uppercase(),
-                    bytes_to_hex(&R2_digest));
+    #[test]
+    fn test_nonce_agg_vectors() {
+        for (indices, expected) in &MUSIG_NONCE_AGG_VALID {
+            let nonces: Vec<_> =
+                indices.iter().map(|i| PubNonce::parse(&hex_to_bytes(MUSIG_NONCE_AGG_PUBNONCES[*i])).unwrap()).collect();
+            let aggnonce = nonce_agg(&nonces);
+            assert_eq!(aggnonce.serialize()[..], hex_to_bytes(expected)[..]);
+            assert_eq!(AggNonce::parse(&aggnonce.serialize()).unwrap(), aggnonce);
+        }
+        for (indices, invalid) in &MUSIG_NONCE_AGG_INVALID {
+            for (i, index) in indices.iter().enumerate() {
+                assert_eq!(PubNonce::parse(&hex_to_bytes(MUSIG_NONCE_AGG_PUBNONCES[*index])).is_err(), i == *invalid);
+            }
+        }
+    }
 
-        assert_eq!("bc2c379a7b6ad82b40a7cf280697505ccc039370e50eb155324dcac3b5faa6a5".to_uppercase(),
-                    bytes_to_hex(&R3_digest));
-        */
+    #[test]
+    fn test_sign_verify_vectors() {
+        let sk = PrivateKey::from_serialized(&hex_to_bytes(MUSIG_SIGN_SK));
+        // The error and verification failure cases all sign message 0.
+        let msg = hex_to_bytes(MUSIG_SIGN_MSG[0]);
+        for (key_indices, nonce_indices, aggnonce_index, msg_index, signer, expected) in &MUSIG_SIGN_VALID {
+            let ctx = KeyAggContext::new(&pubkeys(&MUSIG_SIGN_PUBKEYS, key_indices).unwrap()).unwrap();
+            let nonces = pubnonces(nonce_indices).unwrap();
+            let aggnonce = AggNonce::parse(&hex_to_bytes(MUSIG_SIGN_AGGNONCES[*aggnonce_index])).unwrap();
+            assert_eq!(nonce_agg(&nonces), aggnonce);
+
+            let session = Session::new(&ctx, &aggnonce, &hex_to_bytes(MUSIG_SIGN_MSG[*msg_index]));
+            let psig = session.partial_sign(secnonce(MUSIG_SIGN_SECNONCES[0]), &sk).unwrap();
+            assert_eq!(psig.serialize()[..], hex_to_bytes(expected)[..]);
+            assert!(session.partial_sig_verify(&psig, &nonces[*signer], &ctx.pubkeys()[*signer]));
+        }
 
-        let (calculated_r_agg, negated) = agg_schnorr_nonces(&[r1, r2, r3]);
+        for (key_indices, aggnonce_index, secnonce_index) in &MUSIG_SIGN_ERROR {
+            let ctx = match pubkeys(&MUSIG_SIGN_PUBKEYS, key_indices) {
+                Ok(keys) => KeyAggContext::new(&keys).unwrap(),
+                Err(_) => continue,
+            };
+            let aggnonce = match AggNonce::parse(&hex_to_bytes(MUSIG_SIGN_AGGNONCES[*aggnonce_index])) {
+                Ok(aggnonce) => aggnonce,
+                Err(_) => continue,
+            };
+            let session = Session::new(&ctx, &aggnonce, &msg);
+            assert!(session.partial_sign(secnonce(MUSIG_SIGN_SECNONCES[*secnonce_index]), &sk).is_err());
+        }
 
-        if negated {
-            key_1 = key_1.negate();
-            key_2 = key_2.negate();
-            key_3 = key_3.negate();
+        let aggnonce = AggNonce::parse(&hex_to_bytes(MUSIG_SIGN_AGGNONCES[0])).unwrap();
+        for (psig, key_indices, nonce_indices, signer) in &MUSIG_VERIFY_FAIL {
+            let (keys, nonces, psig) = match (
+                pubkeys(&MUSIG_SIGN_PUBKEYS, key_indices),
+                pubnonces(nonce_indices),
+                PartialSignature::parse(&hex_to_bytes(psig)),
+            ) {
+                (Ok(keys), Ok(nonces), Ok(psig)) => (keys, nonces, psig),
+                _ => continue,
+            };
+            let session = Session::new(&KeyAggContext::new(&keys).unwrap(), &aggnonce, &msg);
+            assert!(!session.partial_sig_verify(&psig, &nonces[*signer], &keys[*signer]));
         }
+    }
 
-        let correct_r_agg = "03f90c3416d74049bf27b5563067c58401ff466e4bb04e1fa4d51ae4c93b4a8316".to_uppercase();
+    // Signing twice with the same `SecNonce` doesn't compile since `partial_sign` consumes it,
+    // this checks the persisted bytes and that the zeroed bytes a used nonce leaves behind can't sign.
+    #[test]
+    fn test_secnonce_bytes() {
+        let sk = PrivateKey::from_serialized(&hex_to_bytes(MUSIG_SIGN_SK));
+        let ctx = KeyAggContext::new(&pubkeys(&MUSIG_SIGN_PUBKEYS, &[0, 1, 2]).unwrap()).unwrap();
+        let aggnonce = AggNonce::parse(&hex_to_bytes(MUSIG_SIGN_AGGNONCES[0])).unwrap();
+        let session = Session::new(&ctx, &aggnonce, &hex_to_bytes(MUSIG_SIGN_MSG[0]));
+
+        let bytes = secnonce(MUSIG_SIGN_SECNONCES[0]).dangerous_into_bytes();
+        assert_eq!(bytes[..], hex_to_bytes(MUSIG_SIGN_SECNONCES[0])[..]);
+        assert!(session.partial_sign(SecNonce::dangerous_from_bytes(bytes), &sk).is_ok());
+        // A zeroed nonce is what's left in memory after signing.
+        assert!(session.partial_sign(secnonce(MUSIG_SIGN_SECNONCES[1]), &sk).is_err());
+    }
+
+    // The kinds of cases BIP327's det_sign vectors cover, on the sign/verify vector keys and nonces.
+    #[test]
+    fn test_deterministic_sign_cases() {
+        let sk = PrivateKey::from_serialized(&hex_to_bytes(MUSIG_SIGN_SK));
+        let others = pubnonces(&[1, 2]).unwrap();
+        let aggothernonce = nonce_agg(&others);
+        let ctx = KeyAggContext::new(&pubkeys(&MUSIG_SIGN_PUBKEYS, &[1, 2, 0]).unwrap()).unwrap();
+        let tweaked = apply_tweaks(ctx.clone(), &MUSIG_TWEAK_TWEAKS, &[(0, true), (1, false)]).unwrap();
+        for ctx in [ctx, tweaked].iter() {
+            for (msg, rand) in MUSIG_SIGN_MSG.iter().zip([Some([0u8; 32]), None, Some([0xff; 32])].iter()) {
+                let msg = hex_to_bytes(msg);
+                let (pubnonce, psig) = deterministic_sign(&sk, &aggothernonce, ctx, &msg, rand.as_ref()).unwrap();
+                let session = Session::new(ctx, &nonce_agg(&[others[0].clone(), others[1].clone(), pubnonce.clone()]), &msg);
+                assert!(session.partial_sig_verify(&psig, &pubnonce, &ctx.pubkeys()[2]));
+            }
+        }
 
-        assert_eq!(correct_r_agg, bytes_to_hex(&calculated_r_agg.compressed()));
+        // The signer's key isn't one of the keys.
+        let ctx = KeyAggContext::new(&pubkeys(&MUSIG_SIGN_PUBKEYS, &[1, 2]).unwrap()).unwrap();
+        assert!(deterministic_sign(&sk, &aggothernonce, &ctx, &hex_to_bytes(MUSIG_SIGN_MSG[0]), None).is_err());
+    }
 
-        // Partial Signatures
-        let msg = b"transaction".hash_digest();
-        let s1 = sign_musig(&privkey_1_c, &key_1, &calculated_r_agg, &agg_pubkey, &msg);
-        let s2 = sign_musig(&privkey_2_c, &key_2, &calculated_r_agg, &agg_pubkey, &msg);
-        let s3 = sign_musig(&privkey_3_c, &key_3, &calculated_r_agg, &agg_pubkey, &msg);
+    #[test]
+    fn test_deterministic_sign() {
+        let privkeys: Vec<_> = (1..=3u8).map(|i| PrivateKey::from_serialized(&[i; 32])).collect();
+        let pubkeys: Vec<_> = privkeys.iter().map(PrivateKey::generate_pubkey).collect();
+        let ctx = KeyAggContext::new(&pubkeys).unwrap();
+        let msg = b"the last signer keeps no state";
+
+        let (secnonces, mut nonces): (Vec<_>, Vec<_>) = privkeys[..2]
+            .iter()
+            .zip(pubkeys.iter())
+            .enumerate()
+            .map(|(i, (sk, pk))| nonce_gen(&[i as u8; 32], Some(sk), pk, None, None, None))
+            .unzip();
+        let aggothernonce = nonce_agg(&nonces);
+        let (pubnonce, psig) = deterministic_sign(&privkeys[2], &aggothernonce, &ctx, msg, Some(&[7u8; 32])).unwrap();
+        assert_eq!(
+            deterministic_sign(&privkeys[2], &aggothernonce, &ctx, msg, Some(&[7u8; 32])).unwrap(),
+            (pubnonce.clone(), psig.clone())
+        );
+        assert_ne!(deterministic_sign(&privkeys[2], &aggothernonce, &ctx, msg, None).unwrap().0, pubnonce);
+        assert_ne!(deterministic_sign(&privkeys[2], &aggothernonce, &ctx, b"another message", Some(&[7u8; 32])).unwrap().0, pubnonce);
+
+        nonces.push(pubnonce);
+        let session = Session::new(&ctx, &nonce_agg(&nonces), msg);
+        let mut psigs: Vec<_> =
+            privkeys.iter().zip(secnonces).map(|(sk, secnonce)| session.partial_sign(secnonce, sk).unwrap()).collect();
+        psigs.push(psig);
+        for i in 0..3 {
+            assert!(session.partial_sig_verify(&psigs[i], &nonces[i], &pubkeys[i]));
+        }
+        let sig = session.partial_sig_agg(&psigs);
+        assert!(PublicKey::from_xonly(&ctx.x_only_agg_pubkey()).unwrap().verify_schnorr_msg(msg, sig));
+    }
 
-        assert_eq!("911A969C1BD3A3E9881026D51CD3936017EABB53CA35966E1A942E55A3F9EF1F", bytes_to_hex(&s1));
+    #[test]
+    fn test_sig_agg_vectors() {
+        let msg = hex_to_bytes(MUSIG_SIG_AGG_MSG);
+        for (key_indices, tweaks, aggnonce, psig_indices, expected) in &MUSIG_SIG_AGG_VALID {
+            let ctx = KeyAggContext::new(&pubkeys(&MUSIG_SIG_AGG_PUBKEYS, key_indices).unwrap()).unwrap();
+            let ctx = apply_tweaks(ctx, &MUSIG_SIG_AGG_TWEAKS, tweaks).unwrap();
+            let session = Session::new(&ctx, &AggNonce::parse(&hex_to_bytes(aggnonce)).unwrap(), &msg);
+            let psigs: Vec<_> =
+                psig_indices.iter().map(|i| PartialSignature::parse(&hex_to_bytes(MUSIG_SIG_AGG_PSIGS[*i])).unwrap()).collect();
+
+            let sig = session.partial_sig_agg(&psigs);
+            assert_eq!(sig.serialize()[..], hex_to_bytes(expected)[..]);
+            let agg_pubkey = PublicKey::from_xonly(&ctx.x_only_agg_pubkey()).unwrap();
+            assert!(agg_pubkey.verify_schnorr_msg(&msg, sig));
+        }
+        assert!(PartialSignature::parse(&hex_to_bytes(MUSIG_SIG_AGG_PSIGS[8])).is_err());
+    }
 
-        assert_eq!("6C7D713005AA61E2BBF7A1E19AA0D53E1A33DD9EF0C8449F18FE4FF2BAF6D057", bytes_to_hex(&s2));
+    #[test]
+    fn test_musig_sign() {
+        let privkeys: Vec<_> = (1..=3u8).map(|i| PrivateKey::from_serialized(&[i; 32])).collect();
+        let pubkeys: Vec<_> = privkeys.iter().map(PrivateKey::generate_pubkey).collect();
+        let ctx = KeyAggContext::new_sorted(&pubkeys).unwrap();
+        let msg = b"MuSig2 with three signers";
+
+        let (secnonces, nonces): (Vec<_>, Vec<_>) = privkeys
+            .iter()
+            .zip(pubkeys.iter())
+            .enumerate()
+            .map(|(i, (sk, pk))| nonce_gen(&[i as u8; 32], Some(sk), pk, Some(&ctx.x_only_agg_pubkey()), Some(msg), None))
+            .unzip();
+        let session = Session::new(&ctx, &nonce_agg(&nonces), msg);
+
+        let psigs: Vec<_> = privkeys.iter().zip(secnonces).map(|(sk, secnonce)| session.partial_sign(secnonce, sk).unwrap()).collect();
+        for i in 0..3 {
+            assert!(session.partial_sig_verify(&psigs[i], &nonces[i], &pubkeys[i]));
+            assert!(!session.partial_sig_verify(&psigs[i], &nonces[(i + 1) % 3], &pubkeys[i]));
+        }
+        let sig = session.partial_sig_agg(&psigs);
+        let agg_pubkey = PublicKey::from_xonly(&ctx.x_only_agg_pubkey()).unwrap();
+        assert!(agg_pubkey.verify_schnorr_msg(msg, sig));
+    }
 
-        assert_eq!("588CFD807EEC08AE32E50180E1E0CE6DDF8A4543B60BAB51A01D2F16B7F7B65F", bytes_to_hex(&s3));
+    #[test]
+    fn test_partial_sig_agg_blame() {
+        let privkeys: Vec<_> = (1..=4u8).map(|i| PrivateKey::from_serialized(&[i; 32])).collect();
+        let pubkeys: Vec<_> = privkeys.iter().map(PrivateKey::generate_pubkey).collect();
+        let ctx = KeyAggContext::new(&pubkeys).unwrap();
+        let msg = b"find the cheaters";
+
+        let (secnonces, nonces): (Vec<_>, Vec<_>) = privkeys
+            .iter()
+            .zip(pubkeys.iter())
+            .enumerate()
+            .map(|(i, (sk, pk))| nonce_gen(&[i as u8; 32], Some(sk), pk, None, None, None))
+            .unzip();
+        let session = Session::new(&ctx, &nonce_agg(&nonces), msg);
+        let mut psigs: Vec<_> =
+            privkeys.iter().zip(secnonces).map(|(sk, secnonce)| session.partial_sign(secnonce, sk).unwrap()).collect();
+
+        let sig = session.partial_sig_agg_verified(&psigs, &nonces, &pubkeys).unwrap();
+        assert_eq!(sig, session.partial_sig_agg(&psigs));
+
+        // Signer 1 sends garbage and signer 3 sends signer 0's partial signature.
+        psigs[1] = PartialSignature(Integer::from(1));
+        psigs[3] = psigs[0].clone();
+        assert_eq!(
+            session.partial_sig_agg_verified(&psigs, &nonces, &pubkeys),
+            Err(PartialSigAggError::InvalidPartialSignatures(vec![1, 3]))
+        );
+        // A missing public nonce or key.
+        assert_eq!(session.partial_sig_agg_verified(&psigs, &nonces[1..], &pubkeys), Err(PartialSigAggError::LengthMismatch));
+        assert_eq!(session.partial_sig_agg_verified(&psigs, &nonces, &pubkeys[..3]), Err(PartialSigAggError::LengthMismatch));
+        let sig = session.partial_sig_agg(&psigs);
+        assert!(!PublicKey::from_xonly(&ctx.x_only_agg_pubkey()).unwrap().verify_schnorr_msg(msg, sig));
+    }
 
-        // signature aggregation
-        let sig_agg = aggregate_musig_signatures(&[s1, s2, s3], &calculated_r_agg);
+    #[test]
+    fn test_musig_sign_tweaked() {
+        let privkeys: Vec<_> = (4..=5u8).map(|i| PrivateKey::from_serialized(&[i; 32])).collect();
+        let pubkeys: Vec<_> = privkeys.iter().map(PrivateKey::generate_pubkey).collect();
+        let internal = KeyAggContext::new_sorted(&pubkeys).unwrap();
+        let derived = internal.plain_tweak_add(&Scalar([7u8; 32])).unwrap();
+        let merkle_root = [9u8; 32];
+        let ctx = derived.tap_tweak(Some(&merkle_root)).unwrap();
+        let msg = b"spend a taproot output with a MuSig2 internal key";
+
+        let (secnonces, nonces): (Vec<_>, Vec<_>) = privkeys
+            .iter()
+            .zip(pubkeys.iter())
+            .enumerate()
+            .map(|(i, (sk, pk))| nonce_gen(&[i as u8; 32], Some(sk), pk, None, None, None))
+            .unzip();
+        let session = Session::new(&ctx, &nonce_agg(&nonces), msg);
+        let psigs: Vec<_> = privkeys.iter().zip(secnonces).map(|(sk, secnonce)| session.partial_sign(secnonce, sk).unwrap()).collect();
+        for i in 0..2 {
+            assert!(session.partial_sig_verify(&psigs[i], &nonces[i], &pubkeys[i]));
+        }
 
-        assert_eq!("F90C3416D74049BF27B5563067C58401FF466E4BB04E1FA4D51AE4C93B4A83165625054CA06A0E7A76ECCA379955370D56FA014FC1C0E62313DD4ED246B23494",
-                    bytes_to_hex(&sig_agg.serialize()));
+        let sig = session.partial_sig_agg(&psigs);
+        let output_key = PublicKey::from_xonly(&ctx.x_only_agg_pubkey()).unwrap();
+        assert!(output_key.verify_schnorr_msg(msg, sig));
+        let internal_key = derived.agg_pubkey();
+        assert_eq!(internal_key.tap_tweak(Some(&merkle_root)).unwrap(), *ctx.agg_pubkey());
+        assert!(internal_key.verify_tap_commitment(&output_key.x_only(), !ctx.agg_pubkey().has_even_y(), Some(&merkle_root)));
     }
 }
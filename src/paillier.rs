@@ -0,0 +1,171 @@
+use crate::utility::{expand_seed, int_from_bytes};
+use rug::{integer::Order, Integer};
+
+// Paillier encryption with g = n + 1. It's additively homomorphic:
+// Enc(a)*Enc(b) = Enc(a + b) and Enc(a)^k = Enc(k*a), both mod n.
+
+// The number of N-th roots in a `CorrectKeyProof`, together with the small factors check it's ~128 bits of soundness.
+const CORRECT_KEY_ROUNDS: u32 = 11;
+const SMALL_FACTORS_BOUND: u32 = 6370;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaillierPublicKey {
+    n: Integer,
+    nn: Integer,
+}
+
+pub struct PaillierPrivateKey {
+    public: PaillierPublicKey,
+    phi: Integer,
+    // phi^-1 mod n
+    phi_inv: Integer,
+}
+
+impl PaillierPublicKey {
+    pub fn new(n: Integer) -> PaillierPublicKey {
+        let nn = Integer::from(n.square_ref());
+        PaillierPublicKey { n, nn }
+    }
+
+    pub fn n(&self) -> &Integer {
+        &self.n
+    }
+
+    // (1 + n)^m * r^n = (1 + m*n) * r^n mod n^2, `r` has to be a random unit mod n.
+    pub fn encrypt(&self, m: &Integer, r: &Integer) -> Integer {
+        let gm = (Integer::from(m * &self.n) + 1) % &self.nn;
+        let rn = Integer::from(r.pow_mod_ref(&self.n, &self.nn).unwrap());
+        (gm * rn) % &self.nn
+    }
+
+    // Encrypt with randomness derived from `rand`, which must be fresh for every encryption.
+    pub fn encrypt_with_seed(&self, m: &Integer, rand: &[u8; 32]) -> Integer {
+        let len = self.n.significant_bits() as usize / 8 + 16;
+        let r = int_from_bytes(&expand_seed(b"Paillier/randomness", rand, len)) % &self.n;
+        self.encrypt(m, &r)
+    }
+
+    // Enc(a + b)
+    pub fn add(&self, a: &Integer, b: &Integer) -> Integer {
+        Integer::from(a * b) % &self.nn
+    }
+
+    // Enc(k * a)
+    pub fn mul(&self, a: &Integer, k: &Integer) -> Integer {
+        Integer::from(a.pow_mod_ref(k, &self.nn).unwrap())
+    }
+
+    pub fn is_valid_ciphertext(&self, c: &Integer) -> bool {
+        *c > 0 && *c < self.nn && Integer::from(c.gcd_ref(&self.n)) == 1
+    }
+}
+
+impl PaillierPrivateKey {
+    // Generate n = p*q for random primes p and q of `bits / 2` bits each, derived from `rand`.
+    pub fn generate(bits: u32, rand: &[u8; 32]) -> PaillierPrivateKey {
+        let prime_bits = bits / 2;
+        let mut counter = 0u32;
+        let mut random_prime = || loop {
+            counter += 1;
+            let seed = [&rand[..], &counter.to_be_bytes()].concat();
+            let mut candidate = int_from_bytes(&expand_seed(b"Paillier/prime", &seed, prime_bits as usize / 8));
+            // The top two bits make sure p*q has exactly `bits` bits.
+            candidate.set_bit(prime_bits - 1, true);
+            candidate.set_bit(prime_bits - 2, true);
+            let prime = candidate.next_prime();
+            if prime.significant_bits() == prime_bits {
+                return prime;
+            }
+        };
+        loop {
+            let (p, q) = (random_prime(), random_prime());
+            if p == q {
+                continue;
+            }
+            let n = Integer::from(&p * &q);
+            let phi: Integer = (p - 1) * (q - 1);
+            if let Ok(phi_inv) = phi.clone().invert(&n) {
+                return PaillierPrivateKey { public: PaillierPublicKey::new(n), phi, phi_inv };
+            }
+        }
+    }
+
+    pub fn public(&self) -> &PaillierPublicKey {
+        &self.public
+    }
+
+    // m = L(c^phi mod n^2) * phi^-1 mod n, where L(x) = (x - 1) / n.
+    pub fn decrypt(&self, c: &Integer) -> Integer {
+        let PaillierPublicKey { n, nn } = &self.public;
+        let x = c.clone().secure_pow_mod(&self.phi, nn);
+        let l = (x - 1) / n;
+        (l * &self.phi_inv) % n
+    }
+
+    // Proves gcd(n, phi(n)) = 1 (Hazay, Mikkelsen, Rabin, Toft) by giving the N-th roots of
+    // pseudo random values, which only exist for all of them if the key is correct.
+    pub fn correct_key_proof(&self) -> CorrectKeyProof {
+        let n = &self.public.n;
+        let exponent = n.clone().invert(&self.phi).unwrap();
+        let roots = (0..CORRECT_KEY_ROUNDS).map(|i| correct_key_challenge(n, i).secure_pow_mod(&exponent, n)).collect();
+        CorrectKeyProof(roots)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CorrectKeyProof(Vec<Integer>);
+
+impl CorrectKeyProof {
+    pub fn verify(&self, public: &PaillierPublicKey) -> bool {
+        let n = &public.n;
+        if *n <= SMALL_FACTORS_BOUND || (2..SMALL_FACTORS_BOUND).any(|d| n.is_divisible_u(d)) {
+            return false;
+        }
+        self.0.len() == CORRECT_KEY_ROUNDS as usize
+            && self
+                .0
+                .iter()
+                .enumerate()
+                .all(|(i, root)| Integer::from(root.pow_mod_ref(n, n).unwrap()) == correct_key_challenge(n, i as u32))
+    }
+}
+
+fn correct_key_challenge(n: &Integer, i: u32) -> Integer {
+    let seed = [&n.to_digits::<u8>(Order::MsfBe)[..], &i.to_be_bytes()].concat();
+    int_from_bytes(&expand_seed(b"Paillier/correct key", &seed, n.significant_bits() as usize / 8 + 16)) % n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paillier() {
+        let sk = PaillierPrivateKey::generate(512, &[1; 32]);
+        let pk = sk.public();
+        assert_eq!(pk.n().significant_bits(), 512);
+
+        let (a, b) = (Integer::from(123_456_789), Integer::from(987_654_321));
+        let ca = pk.encrypt_with_seed(&a, &[2; 32]);
+        let cb = pk.encrypt_with_seed(&b, &[3; 32]);
+        assert!(pk.is_valid_ciphertext(&ca));
+        assert_ne!(ca, pk.encrypt_with_seed(&a, &[4; 32]));
+        assert_eq!(sk.decrypt(&ca), a);
+        assert_eq!(sk.decrypt(&pk.add(&ca, &cb)), Integer::from(&a + &b));
+        assert_eq!(sk.decrypt(&pk.mul(&ca, &b)), Integer::from(&a * &b));
+        // Everything is mod n.
+        let minus_one = Integer::from(pk.n() - 1);
+        assert_eq!(sk.decrypt(&pk.add(&pk.encrypt_with_seed(&minus_one, &[5; 32]), &ca)), Integer::from(&a - 1));
+    }
+
+    #[test]
+    fn test_correct_key_proof() {
+        let sk = PaillierPrivateKey::generate(512, &[6; 32]);
+        let proof = sk.correct_key_proof();
+        assert!(proof.verify(sk.public()));
+        assert!(!proof.verify(PaillierPrivateKey::generate(512, &[7; 32]).public()));
+
+        assert!(!CorrectKeyProof(proof.0[1..].to_vec()).verify(sk.public()));
+        assert!(!proof.verify(&PaillierPublicKey::new(Integer::from(sk.public().n() * 3))));
+    }
+}
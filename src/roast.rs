@@ -0,0 +1,282 @@
+use crate::frost::{self, KeyPackage, PublicKeyPackage, SignatureShare, SigningCommitments, SigningNonces, SigningPackage};
+use crate::secp256k1::SchnorrSignature;
+use std::collections::{BTreeMap, BTreeSet};
+
+// ROAST (Ruffing et al.), a robust asynchronous coordinator on top of FROST signing.
+// Instead of waiting for one fixed set of signers, the coordinator starts a new FROST session whenever `threshold`
+// signers have responded, so a silent signer only blocks the sessions it's part of. Signers that send invalid
+// shares or unexpected messages are flagged and ignored. With at least `threshold` honest signers it's
+// guaranteed to finish after at most n - threshold + 1 sessions.
+//
+// Every message from a signer carries its share for the session it was in (none the first time) and the
+// commitments for its next session, so a signer is always in at most one session.
+
+// What the coordinator wants done after a message.
+#[derive(Debug, PartialEq)]
+pub enum CoordinatorOutput {
+    // Send the package to all of its signers.
+    StartSession(SigningPackage),
+    // Signing is done.
+    Signature(SchnorrSignature),
+}
+
+pub struct Coordinator {
+    public: PublicKeyPackage,
+    n: u32,
+    msg: Vec<u8>,
+    malicious: BTreeSet<u32>,
+    // Signers waiting for a session, with their latest commitments.
+    responsive: BTreeMap<u32, SigningCommitments>,
+    // The session every signer is currently in.
+    session_of: BTreeMap<u32, usize>,
+    sessions: Vec<(SigningPackage, Vec<SignatureShare>)>,
+    finished: bool,
+}
+
+impl Coordinator {
+    pub fn new(public: PublicKeyPackage, n: u32, msg: &[u8]) -> Result<Coordinator, &'static str> {
+        if public.threshold() > n as usize {
+            return Err("The threshold must be in the range [1, n]");
+        }
+        Ok(Coordinator {
+            public,
+            n,
+            msg: msg.to_vec(),
+            malicious: BTreeSet::new(),
+            responsive: BTreeMap::new(),
+            session_of: BTreeMap::new(),
+            sessions: Vec::new(),
+            finished: false,
+        })
+    }
+
+    // The signers caught misbehaving so far.
+    pub fn malicious(&self) -> Vec<u32> {
+        self.malicious.iter().copied().collect()
+    }
+
+    pub fn sessions_started(&self) -> usize {
+        self.sessions.len()
+    }
+
+    // Handle a message from signer `index`. Messages from flagged signers and after signing finished are ignored.
+    // Fails once too many signers are flagged to ever collect `threshold` shares.
+    pub fn receive(
+        &mut self,
+        index: u32,
+        share: Option<SignatureShare>,
+        next: SigningCommitments,
+    ) -> Result<Option<CoordinatorOutput>, &'static str> {
+        if self.finished || index == 0 || index > self.n || self.malicious.contains(&index) {
+            return Ok(None);
+        }
+        if !self.is_valid_message(index, share.as_ref(), &next) {
+            self.malicious.insert(index);
+            self.responsive.remove(&index);
+            self.session_of.remove(&index);
+            if (self.n as usize) - self.malicious.len() < self.public.threshold() {
+                return Err("Too many malicious signers to ever finish signing");
+            }
+            return Ok(None);
+        }
+
+        if let (Some(share), Some(id)) = (share, self.session_of.remove(&index)) {
+            let (package, shares) = &mut self.sessions[id];
+            shares.push(share);
+            if shares.len() == package.signers().len() {
+                // Every share was verified when it arrived.
+                let signature = frost::aggregate(package, shares, &self.public).map_err(|_| "Aggregating verified shares failed")?;
+                self.finished = true;
+                return Ok(Some(CoordinatorOutput::Signature(signature)));
+            }
+        }
+
+        self.responsive.insert(index, next);
+        if self.responsive.len() < self.public.threshold() {
+            return Ok(None);
+        }
+        let commitments: Vec<_> = std::mem::take(&mut self.responsive).into_values().collect();
+        let package = SigningPackage::new(&commitments, &self.msg)?;
+        for signer in package.signers() {
+            self.session_of.insert(signer, self.sessions.len());
+        }
+        self.sessions.push((package.clone(), Vec::new()));
+        Ok(Some(CoordinatorOutput::StartSession(package)))
+    }
+
+    // A share is expected exactly when the signer is in a session, and it has to verify.
+    fn is_valid_message(&self, index: u32, share: Option<&SignatureShare>, next: &SigningCommitments) -> bool {
+        if next.index() != index || self.responsive.contains_key(&index) {
+            return false;
+        }
+        match (share, self.session_of.get(&index)) {
+            (None, None) => true,
+            (Some(share), Some(id)) => share.index() == index && frost::verify_share(&self.sessions[*id].0, share, &self.public),
+            _ => false,
+        }
+    }
+}
+
+// The signer side: keeps the nonces for the session the signer committed to.
+pub struct Signer {
+    key: KeyPackage,
+    nonces: Option<SigningNonces>,
+}
+
+impl Signer {
+    pub fn new(key: KeyPackage) -> Signer {
+        Signer { key, nonces: None }
+    }
+
+    pub fn index(&self) -> u32 {
+        self.key.index()
+    }
+
+    // The first message to the coordinator. `rand` must be fresh randomness.
+    pub fn initial_commitments(&mut self, rand: &[u8; 32]) -> SigningCommitments {
+        let (nonces, commitments) = frost::commit(&self.key, rand);
+        self.nonces = Some(nonces);
+        commitments
+    }
+
+    // Answer a session started by the coordinator with a share and the commitments for the next session.
+    // `rand` must be fresh randomness.
+    pub fn sign(&mut self, package: &SigningPackage, rand: &[u8; 32]) -> Result<(SignatureShare, SigningCommitments), &'static str> {
+        let nonces = self.nonces.take().ok_or("There are no nonces to sign with")?;
+        let share = frost::sign(package, nonces, &self.key)?;
+        Ok((share, self.initial_commitments(rand)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frost::trusted_dealer_keygen;
+    use crate::secp256k1::{PrivateKey, PublicKey};
+    use crate::utility::tagged_hash;
+    use std::collections::VecDeque;
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum Behavior {
+        Honest,
+        // Never answers after its first message.
+        Silent,
+        // Sends a wrong signature share.
+        WrongShare,
+        // Sends its answer twice.
+        Confused,
+    }
+
+    // Deliver messages to the coordinator in a pseudo random order until it's done,
+    // returns the signature and the coordinator.
+    fn run(threshold: usize, behaviors: &[Behavior], seed: u8) -> (Result<SchnorrSignature, &'static str>, Coordinator) {
+        let n = behaviors.len() as u32;
+        let sk = PrivateKey::from_serialized(&[seed.wrapping_add(1); 32]);
+        let (shares, public) = trusted_dealer_keygen(&sk, threshold, n, &[seed; 32]).unwrap();
+        let mut signers: Vec<_> =
+            shares.into_iter().map(|share| Signer::new(KeyPackage::new(share, public.clone()).unwrap())).collect();
+        let mut coordinator = Coordinator::new(public, n, b"ROAST").unwrap();
+
+        let mut counter = 0u32;
+        let mut rand = || {
+            counter += 1;
+            tagged_hash(b"ROAST test", &[&[seed][..], &counter.to_be_bytes()].concat())
+        };
+        let mut queue: VecDeque<_> =
+            signers.iter_mut().map(|signer| (signer.index(), None, signer.initial_commitments(&rand()))).collect();
+        loop {
+            if queue.is_empty() {
+                return (Err("Ran out of messages"), coordinator);
+            }
+            let pick = rand()[0] as usize % queue.len();
+            let (index, share, next) = queue.remove(pick).unwrap();
+            let package = match coordinator.receive(index, share, next) {
+                Err(e) => return (Err(e), coordinator),
+                Ok(None) => continue,
+                Ok(Some(CoordinatorOutput::Signature(sig))) => return (Ok(sig), coordinator),
+                Ok(Some(CoordinatorOutput::StartSession(package))) => package,
+            };
+            for index in package.signers() {
+                let signer = &mut signers[index as usize - 1];
+                let (mut share, next) = signer.sign(&package, &rand()).unwrap();
+                match behaviors[index as usize - 1] {
+                    Behavior::Honest => queue.push_back((index, Some(share), next)),
+                    Behavior::Silent => (),
+                    Behavior::WrongShare => {
+                        share = SignatureShare::parse(&[&index.to_be_bytes()[..], &[1u8; 32]].concat()).unwrap();
+                        queue.push_back((index, Some(share), next));
+                    }
+                    Behavior::Confused => {
+                        queue.push_back((index, Some(share.clone()), next.clone()));
+                        queue.push_back((index, Some(share), next));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_roast_honest() {
+        let (sig, coordinator) = run(3, &[Behavior::Honest; 5], 1);
+        assert!(sig.is_ok());
+        assert!(coordinator.malicious().is_empty());
+        assert_eq!(coordinator.sessions_started(), 1);
+    }
+
+    #[test]
+    fn test_roast_malicious() {
+        use Behavior::*;
+        let behaviors = [Honest, Silent, WrongShare, Honest, Confused, Honest, Honest];
+        let mut flagged = BTreeSet::new();
+        for seed in 0..4 {
+            let (sig, coordinator) = run(4, &behaviors, seed);
+            let sig = sig.unwrap();
+            let group_pubkey = PublicKey::from_xonly(&coordinator.public.x_only_group_pubkey()).unwrap();
+            assert!(group_pubkey.verify_schnorr_msg(b"ROAST", sig));
+            // Misbehavior is only noticed if the signer was picked for a session.
+            assert!(coordinator.malicious().iter().all(|i| [3, 5].contains(i)));
+            assert!(coordinator.sessions_started() <= 7 - 4 + 1);
+            flagged.extend(coordinator.malicious());
+        }
+        assert!(!flagged.is_empty());
+    }
+
+    #[test]
+    fn test_roast_too_many_malicious() {
+        use Behavior::*;
+        // Any session includes a malicious signer.
+        let (sig, coordinator) = run(2, &[WrongShare, Honest, WrongShare], 3);
+        assert_eq!(sig, Err("Too many malicious signers to ever finish signing"));
+        assert_eq!(coordinator.malicious(), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_unexpected_messages() {
+        let (shares, public) = trusted_dealer_keygen(&PrivateKey::from_serialized(&[9; 32]), 2, 3, &[9; 32]).unwrap();
+        let mut signers: Vec<_> =
+            shares.into_iter().map(|share| Signer::new(KeyPackage::new(share, public.clone()).unwrap())).collect();
+        let mut coordinator = Coordinator::new(public, 3, b"ROAST").unwrap();
+
+        // Commitments for somebody else.
+        let commitments = signers[1].initial_commitments(&[1; 32]);
+        assert_eq!(coordinator.receive(1, None, commitments), Ok(None));
+        assert_eq!(coordinator.malicious(), vec![1]);
+        // Ignored from now on.
+        assert_eq!(coordinator.receive(1, None, signers[0].initial_commitments(&[2; 32])), Ok(None));
+        assert_eq!(coordinator.responsive.len(), 0);
+
+        let package = SigningPackage::new(&[signers[1].initial_commitments(&[3; 32])], b"").unwrap();
+        assert!(signers[2].sign(&package, &[4; 32]).is_err());
+        assert_eq!(coordinator.receive(2, None, signers[1].initial_commitments(&[5; 32])), Ok(None));
+        let package = match coordinator.receive(3, None, signers[2].initial_commitments(&[6; 32])) {
+            Ok(Some(CoordinatorOutput::StartSession(package))) => package,
+            _ => panic!("Expected a new session"),
+        };
+        assert_eq!(package.signers(), vec![2, 3]);
+        let (share, next) = signers[1].sign(&package, &[7; 32]).unwrap();
+        assert_eq!(coordinator.receive(2, Some(share), next), Ok(None));
+        let (share, next) = signers[2].sign(&package, &[8; 32]).unwrap();
+        assert!(matches!(coordinator.receive(3, Some(share), next), Ok(Some(CoordinatorOutput::Signature(_)))));
+        assert_eq!(coordinator.malicious(), vec![1]);
+    }
+}
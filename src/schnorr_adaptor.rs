@@ -0,0 +1,177 @@
+use crate::field::FieldElement;
+use crate::point::Point;
+use crate::secp256k1::{bip340_challenge, get_context, PrivateKey, PublicKey, SchnorrSignature};
+use crate::utility::{int_from_bytes, int_to_bytes, tagged_hash};
+use rug::Integer;
+
+// BIP340 compatible Schnorr adaptor signatures, for scriptless scripts (atomic swaps, DLCs...).
+// A pre-signature under an adaptor point T = t*G verifies against the key like a signature would, but only turns
+// into a valid BIP340 signature with the discrete log t. Anyone holding both the pre-signature and the final
+// signature learns t.
+//
+// The signer picks a nonce k and uses R = k*G + T as the final nonce. BIP340 needs an even R, so if R is odd
+// the signer uses -k, and the final nonce is -R = -k*G - T. The pre-signature keeps R with its parity,
+// so the other side knows whether t gets added or subtracted.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptorSignature {
+    // k*G + T, its x coordinate is the r of the final signature.
+    r: PublicKey,
+    s: Integer,
+}
+
+impl AdaptorSignature {
+    // R (33 bytes, compressed) || s' (32 bytes)
+    pub fn serialize(&self) -> [u8; 65] {
+        let mut res = [0u8; 65];
+        res[..33].copy_from_slice(&self.r.compressed());
+        res[33..].copy_from_slice(&int_to_bytes(&self.s));
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<AdaptorSignature, &'static str> {
+        if ser.len() != 65 {
+            return Err("An adaptor signature should be 65 bytes");
+        }
+        let r = PublicKey::from_compressed(&ser[..33])?;
+        let s = int_from_bytes(&ser[33..]);
+        if s >= get_context().order {
+            return Err("The adaptor signature s overflows the curve order");
+        }
+        Ok(AdaptorSignature { r, s })
+    }
+
+    // Check that the pre-signature turns into a valid signature of `msg` under `pubkey` with the discrete log of `adaptor`.
+    // The key is treated as x-only, like in BIP340.
+    #[allow(non_snake_case)]
+    pub fn pre_verify(&self, pubkey: &PublicKey, msg: &[u8], adaptor: &PublicKey) -> bool {
+        let secp = get_context();
+        let P = if pubkey.has_even_y() { pubkey.clone() } else { pubkey.clone().negate() };
+        let e = bip340_challenge(&self.r.x_only(), &P.x_only(), msg).num;
+        // s'*G - e*P should be R - T, or T - R if R is odd.
+        let R = Point::from(self.r.clone());
+        let T = Point::from(adaptor.clone());
+        let expected =
+            if self.r.has_even_y() { R + Point::from(adaptor.clone().negate()) } else { Point::from(self.r.clone().negate()) + T };
+        secp.mul_generator(&self.s) + (&secp.order - e) * Point::from(P) == expected
+    }
+
+    // The final BIP340 signature, `secret` has to be the discrete log of the adaptor point.
+    pub fn adapt(&self, secret: &PrivateKey) -> SchnorrSignature {
+        let order = &get_context().order;
+        let s = if self.r.has_even_y() {
+            Integer::from(&self.s + &secret.scalar)
+        } else {
+            Integer::from(&self.s + order) - &secret.scalar
+        };
+        let s = s % order;
+        SchnorrSignature::new(&self.r.x_only(), &int_to_bytes(&s))
+    }
+
+    // Recover the discrete log of `adaptor` from the final signature made out of this pre-signature.
+    pub fn extract(&self, sig: &SchnorrSignature, adaptor: &PublicKey) -> Result<PrivateKey, &'static str> {
+        let order = &get_context().order;
+        let sig = sig.serialize();
+        if sig[..32] != self.r.x_only() {
+            return Err("The signature doesn't belong to the adaptor signature");
+        }
+        let s = int_from_bytes(&sig[32..]);
+        let t = if self.r.has_even_y() { s + order - &self.s } else { Integer::from(&self.s + order) - s };
+        let secret = PrivateKey::new(t % order);
+        if secret.scalar == 0 || secret.generate_pubkey() != *adaptor {
+            return Err("The extracted secret doesn't match the adaptor point");
+        }
+        Ok(secret)
+    }
+}
+
+// Pre-sign `msg` under the adaptor point `adaptor`, the message is used as is (any length, no pre-hashing).
+// The nonce is derived like in BIP340 with `aux_rand` as the auxiliary randomness, and also commits to the adaptor point.
+#[allow(non_snake_case)]
+pub fn pre_sign(sk: &PrivateKey, msg: &[u8], adaptor: &PublicKey, aux_rand: &[u8; 32]) -> Result<AdaptorSignature, &'static str> {
+    let secp = get_context();
+    let order = &secp.order;
+    if sk.scalar == 0 || sk.scalar >= *order {
+        return Err("The private key is not in the range [1, n-1]");
+    }
+    let P = sk.generate_pubkey();
+    let d = if P.has_even_y() { sk.scalar.clone() } else { Integer::from(order - &sk.scalar) };
+    let mut masked = int_to_bytes(&d);
+    let aux_hash = tagged_hash(b"SchnorrAdaptor/aux", aux_rand);
+    masked.iter_mut().zip(aux_hash.iter()).for_each(|(byte, aux)| *byte ^= aux);
+
+    let rand = tagged_hash(b"SchnorrAdaptor/nonce", &[&masked[..], &adaptor.compressed(), &P.x_only(), msg].concat());
+    let mut k = int_from_bytes(&rand) % order;
+    if k == 0 {
+        return Err("The nonce is zero");
+    }
+    let R = secp.mul_generator(&k) + Point::from(adaptor.clone());
+    if R.is_on_infinity() {
+        return Err("The nonce cancels the adaptor point");
+    }
+    let R = PublicKey::from(R);
+    if !R.has_even_y() {
+        k = order - k;
+    }
+    let e = bip340_challenge(&R.x_only(), &P.x_only(), msg);
+    let k = FieldElement::new(k, order.clone());
+    let s = PrivateKey::sign_schnorr_raw(&d, k, e, Some(R.clone().into())).serialize();
+    Ok(AdaptorSignature { r: R, s: int_from_bytes(&s[32..]) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptor_signature() {
+        let mut parities = [false; 2];
+        for i in 1..=8u8 {
+            let sk = PrivateKey::from_serialized(&[i; 32]);
+            let secret = PrivateKey::from_serialized(&[i + 0x40; 32]);
+            let (pubkey, adaptor) = (sk.generate_pubkey(), secret.generate_pubkey());
+            let msg = [b"adaptor".as_ref(), &[i]].concat();
+
+            let presig = pre_sign(&sk, &msg, &adaptor, &[i; 32]).unwrap();
+            parities[presig.r.has_even_y() as usize] = true;
+            assert!(presig.pre_verify(&pubkey, &msg, &adaptor));
+            assert!(presig.pre_verify(&pubkey.clone().negate(), &msg, &adaptor));
+            // A pre-signature is not a signature.
+            assert!(!pubkey.verify_schnorr_msg(&msg, SchnorrSignature::parse_slice(&presig.serialize()[1..])));
+
+            let sig = presig.adapt(&secret);
+            assert!(pubkey.verify_schnorr_msg(&msg, SchnorrSignature::parse(sig.serialize())));
+            assert_eq!(presig.extract(&sig, &adaptor).unwrap().serialize(), secret.serialize());
+            assert_eq!(AdaptorSignature::parse(&presig.serialize()).unwrap(), presig);
+        }
+        // Both cases of the final nonce parity were covered.
+        assert_eq!(parities, [true, true]);
+    }
+
+    #[test]
+    fn test_invalid_adaptor_signature() {
+        let sk = PrivateKey::from_serialized(&[0x11; 32]);
+        let secret = PrivateKey::from_serialized(&[0x22; 32]);
+        let (pubkey, adaptor) = (sk.generate_pubkey(), secret.generate_pubkey());
+        let other = PrivateKey::from_serialized(&[0x33; 32]);
+        let presig = pre_sign(&sk, b"msg", &adaptor, &[0; 32]).unwrap();
+
+        assert!(!presig.pre_verify(&pubkey, b"other msg", &adaptor));
+        assert!(!presig.pre_verify(&other.generate_pubkey(), b"msg", &adaptor));
+        assert!(!presig.pre_verify(&pubkey, b"msg", &other.generate_pubkey()));
+        let mut tampered = presig.clone();
+        tampered.s += 1;
+        assert!(!tampered.pre_verify(&pubkey, b"msg", &adaptor));
+
+        // The wrong secret gives an invalid signature, and nothing can be extracted from it.
+        let sig = presig.adapt(&other);
+        assert!(!pubkey.verify_schnorr_msg(b"msg", SchnorrSignature::parse(sig.serialize())));
+        assert!(presig.extract(&sig, &adaptor).is_err());
+        let unrelated = pre_sign(&sk, b"msg2", &adaptor, &[0; 32]).unwrap().adapt(&secret);
+        assert_eq!(presig.extract(&unrelated, &adaptor).err(), Some("The signature doesn't belong to the adaptor signature"));
+
+        assert!(AdaptorSignature::parse(&presig.serialize()[1..]).is_err());
+        assert!(AdaptorSignature::parse(&[&presig.serialize()[..33], &[0xff; 32][..]].concat()).is_err());
+        assert!(pre_sign(&PrivateKey::new(0), b"msg", &adaptor, &[0; 32]).is_err());
+    }
+}
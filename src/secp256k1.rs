@@ -1,9 +1,11 @@
-use crate::field::FieldElement;
+use crate::field::{mod_and_new, FieldElement};
 use crate::hash::{HashDigest, HashTrait};
 use crate::hmac_sha2::{HmacSha256, HmacSha256Drbg};
 use crate::jacobi;
 use crate::jacobi::Jacobi;
+use crate::ladder;
 use crate::point::{Group, Point};
+use crate::utility::tagged_hash;
 use rug::{integer::Order, Integer};
 use std::{
     fmt,
@@ -18,6 +20,7 @@ pub struct Secp256k1 {
     pub modulo: Integer,
     pub order: Integer,
     generator: Point,
+    generator_table: Vec<Point>,
 }
 
 impl Secp256k1 {
@@ -44,7 +47,51 @@ impl Secp256k1 {
         let b = Integer::from(Self::b);
         let group = Group { a, b };
         let point = Point::new_with_group(x, y, &p, group).unwrap();
-        Secp256k1 { generator: point, modulo: p, order: n }
+        let generator_table = Self::doublings_table(&point);
+        Secp256k1 { generator: point, modulo: p, order: n, generator_table }
+    }
+
+    // Precompute G*2^i for every bit of the order,
+    // so multiplying the generator only needs the additions.
+    fn doublings_table(generator: &Point) -> Vec<Point> {
+        let mut table = Vec::with_capacity(256);
+        let mut current = generator.clone();
+        for _ in 0..256 {
+            let next = current.clone() + current.clone();
+            table.push(current);
+            current = next;
+        }
+        table
+    }
+
+    // Multiply the generator by `k` using the precomputed doublings table.
+    // The context is shared, so every thread gets the table for free.
+    pub(crate) fn mul_generator(&self, k: &Integer) -> Point {
+        let k = mod_and_new(k.clone(), &self.order).num;
+        let mut result = self.generator.gen_zero();
+        for (i, point) in self.generator_table.iter().enumerate() {
+            if k.get_bit(i as u32) {
+                result = result + point.clone();
+            }
+        }
+        result
+    }
+
+    // sum(k_i * P_i) with Strauss' method: all the terms share the same doublings,
+    // so verifying many terms at once costs a lot less than multiplying them one by one.
+    pub(crate) fn multi_mul(&self, terms: &[(Integer, Point)]) -> Point {
+        let scalars: Vec<_> = terms.iter().map(|(k, _)| mod_and_new(k.clone(), &self.order).num).collect();
+        let bits = scalars.iter().map(Integer::significant_bits).max().unwrap_or(0);
+        let mut result = self.generator.gen_zero();
+        for i in (0..bits).rev() {
+            result = result.clone() + result;
+            for (k, (_, point)) in scalars.iter().zip(terms) {
+                if k.get_bit(i) {
+                    result = result + point.clone();
+                }
+            }
+        }
+        result
     }
     #[allow(clippy::suspicious_arithmetic_impl)]
 
@@ -84,7 +131,7 @@ impl Secp256k1 {
 }
 
 pub struct PrivateKey {
-    scalar: Integer,
+    pub(crate) scalar: Integer,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -140,7 +187,13 @@ impl PublicKey {
 
     pub fn from_compressed(ser: &[u8]) -> Result<PublicKey, &'static str> {
         let secp = get_context();
+        if ser.len() != 33 {
+            return Err("A compressed public key should be 33 bytes");
+        }
         let x = FieldElement::from_serialize(&ser[1..33], &secp.modulo);
+        if x.num >= secp.modulo {
+            return Err("The x coordinate is bigger than the field size");
+        }
         let mut y = secp.generator.group.get_y(&x);
         let is_even = y.is_even();
         if (ser[0] == 0x02 && !is_even) || (ser[0] == 0x03 && is_even) {
@@ -158,14 +211,22 @@ impl PublicKey {
     // TODO: Maxwell's trick: https://github.com/bitcoin-core/secp256k1/blob/abe2d3e/src/ecdsa_impl.h#L238-L253
     #[allow(non_snake_case)]
     pub(crate) fn verify_raw(&self, z: FieldElement, r: FieldElement, s: FieldElement) -> bool {
-        let G = get_context().generator();
-        let u1 = z / &s;
-        let u2 = r.clone() / &s;
-        let point: Point = (u1.num * G) + (u2.num * self.point.clone());
+        let point = self.verify_raw_point(z, r.clone(), s);
         point.x.num == r.num // Sometimes r.num is only 31 bytes. need to take a closer look.
     }
 
+    // (z/s)*G + (r/s)*P, the nonce point k*G of a valid signature.
+    pub(crate) fn verify_raw_point(&self, z: FieldElement, r: FieldElement, s: FieldElement) -> Point {
+        let u1 = z / &s;
+        let u2 = r / &s;
+        get_context().mul_generator(&u1.num) + (u2.num * self.point.clone())
+    }
+
     pub fn verify(&self, msg: &[u8], sig: Signature, to_hash: bool) -> bool {
+        self.verify_ref(msg, &sig, to_hash)
+    }
+
+    pub(crate) fn verify_ref(&self, msg: &[u8], sig: &Signature, to_hash: bool) -> bool {
         let order = &get_context().order;
         let msg_hash = get_hashed_message_if(msg, to_hash);
         let z = FieldElement::from_serialize(&msg_hash, order);
@@ -219,6 +280,93 @@ impl PublicKey {
 
         PublicKey { point }
     }
+
+    // Parse a BIP340 x-only public key, the point is the one with the even y.
+    pub fn from_xonly(ser: &[u8]) -> Result<PublicKey, &'static str> {
+        let secp = get_context();
+        if ser.len() != 32 {
+            return Err("An x-only public key should be 32 bytes");
+        }
+        let x = FieldElement::from_serialize(ser, &secp.modulo);
+        if x.num >= secp.modulo {
+            return Err("The x coordinate is bigger than the field size");
+        }
+        let mut y = secp.generator.group.get_y(&x);
+        if !y.is_even() {
+            y.reflect();
+        }
+        let point = Point { x, y, group: secp.generator.group.clone() };
+        if !point.is_on_curve() {
+            return Err("The public key is not on the curve");
+        }
+        Ok(PublicKey { point })
+    }
+
+    pub fn x_only(&self) -> [u8; 32] {
+        self.point.x.clone().serialize_num()
+    }
+
+    pub fn has_even_y(&self) -> bool {
+        self.point.y.is_even()
+    }
+
+    // BIP340 verification, the message is used as is (any length, no pre-hashing).
+    // The key is treated as x-only, so a key with an odd y verifies as its negation.
+    #[allow(non_snake_case)]
+    pub fn verify_schnorr_msg(&self, msg: &[u8], sig: SchnorrSignature) -> bool {
+        let secp = get_context();
+        let r = Integer::from_digits(&sig.0.r.0, Order::MsfBe);
+        let s = Integer::from_digits(&sig.0.s.0, Order::MsfBe);
+        if r >= secp.modulo || s >= secp.order || self.point.is_on_infinity() {
+            return false;
+        }
+        let P = if self.has_even_y() { self.clone() } else { self.clone().negate() };
+        let mut e = bip340_challenge(&sig.0.r.0, &P.x_only(), msg);
+        e.reflect();
+
+        let R = secp.mul_generator(&s) + e.num * P.point;
+        if R.is_on_infinity() || !R.y.is_even() {
+            return false;
+        }
+        R.x.num == r
+    }
+
+    // P + tweak*G, like libsecp256k1's `ec_pubkey_tweak_add`.
+    // Fails if the tweak overflows the order or if the result is the point at infinity.
+    pub fn add_exp_tweak(&self, tweak: &Scalar) -> Result<PublicKey, &'static str> {
+        let tweak = tweak_to_integer(tweak)?;
+        let point = self.point.clone() + get_context().mul_generator(&tweak);
+        if point.is_on_infinity() {
+            return Err("The tweaked public key is the point at infinity");
+        }
+        Ok(PublicKey { point })
+    }
+
+    // P * tweak, like libsecp256k1's `ec_pubkey_tweak_mul`.
+    // Fails if the tweak is zero or overflows the order.
+    pub fn mul_tweak(&self, tweak: &Scalar) -> Result<PublicKey, &'static str> {
+        let tweak = tweak_to_integer(tweak)?;
+        if tweak == 0 {
+            return Err("The tweak is zero");
+        }
+        Ok(PublicKey { point: tweak * self.point.clone() })
+    }
+
+    // lift_x(P.x) + tweak*G, like libsecp256k1's `xonly_pubkey_tweak_add`.
+    // Returns the full tweaked key, its x-only serialization and parity are what `tweak_add_check` expects.
+    pub fn xonly_add_exp_tweak(&self, tweak: &Scalar) -> Result<PublicKey, &'static str> {
+        let internal = if self.has_even_y() { self.clone() } else { self.clone().negate() };
+        internal.add_exp_tweak(tweak)
+    }
+
+    // Check that `tweaked_x` and `tweaked_parity` (true for odd y) are the result of tweaking this x-only key with `tweak`,
+    // like libsecp256k1's `xonly_pubkey_tweak_add_check`.
+    pub fn tweak_add_check(&self, tweaked_x: &[u8; 32], tweaked_parity: bool, tweak: &Scalar) -> bool {
+        match self.xonly_add_exp_tweak(tweak) {
+            Ok(tweaked) => &tweaked.x_only() == tweaked_x && tweaked.has_even_y() != tweaked_parity,
+            Err(_) => false,
+        }
+    }
 }
 
 impl PrivateKey {
@@ -227,25 +375,78 @@ impl PrivateKey {
     }
 
     pub fn generate_pubkey(&self) -> PublicKey {
-        let point = &self.scalar * get_context().generator();
+        let point = get_context().mul_generator(&self.scalar);
         PublicKey { point }
     }
 
-    pub fn ecdh(&self, pubkey: &PublicKey) -> [u8; 32] {
-        let point: Point = &self.scalar * pubkey.point.clone();
-        let x = point.x.serialize_num();
-        let y = if point.y.is_even() { 0x02 } else { 0x03 };
-        let mut hash = HashDigest::default();
-        hash.input(&[y]);
-        hash.input(&x);
-        let mut result = [0u8; 32];
-        result.copy_from_slice(&hash.result());
-        result
+    // ECDH like libsecp256k1's default: SHA256 of the compressed shared point, (0x02 | y parity) || x.
+    pub fn ecdh(&self, pubkey: &PublicKey) -> Result<[u8; 32], &'static str> {
+        self.ecdh_with_hash(pubkey, |x, y| {
+            let mut hash = HashDigest::default();
+            hash.input(&[0x02 | (y[31] & 1)]);
+            hash.input(x);
+            let mut result = [0u8; 32];
+            result.copy_from_slice(&hash.result());
+            result
+        })
+    }
+
+    // ECDH with a custom hash of the shared point's x and y, like the `hashfp` of libsecp256k1's `ecdh`.
+    pub fn ecdh_with_hash<F, T>(&self, pubkey: &PublicKey, hash: F) -> Result<T, &'static str>
+    where
+        F: FnOnce(&[u8; 32], &[u8; 32]) -> T,
+    {
+        let point = self.shared_point(pubkey)?;
+        Ok(hash(&point.x.serialize_num(), &point.y.serialize_num()))
+    }
+
+    // The raw x-coordinate of the shared point, for protocols that hash it themselves.
+    // Like `ecdh` this multiplies with the constant time co-Z ladder, so its timing doesn't depend on the private key.
+    pub fn ecdh_x_only(&self, pubkey: &PublicKey) -> Result<[u8; 32], &'static str> {
+        self.check_valid()?;
+        check_valid_point(&pubkey.point)?;
+        self.shared_x(&pubkey.point.x)
+    }
+
+    // The x-coordinate of the shared point from only the x-coordinate of the other key.
+    // Both points with that x give the same result, so the y is never needed.
+    // The x is public, so checking it's on the curve first leaks nothing.
+    pub fn ecdh_from_x(&self, their_x: &[u8; 32]) -> Result<[u8; 32], &'static str> {
+        self.check_valid()?;
+        let secp = get_context();
+        let x = Integer::from_digits(their_x, Order::MsfBe);
+        if x >= secp.modulo {
+            return Err("The x-coordinate overflows the field");
+        }
+        let x = FieldElement::new(x, &secp.modulo);
+        let y2 = x.clone().pow_u(3) + &secp.generator.group.b;
+        if !y2.is_square() {
+            return Err("The x-coordinate is not on the curve");
+        }
+        self.shared_x(&x)
+    }
+
+    fn shared_x(&self, x: &FieldElement) -> Result<[u8; 32], &'static str> {
+        match ladder::mul_x(x, &get_context().generator.group, &self.scalar) {
+            Some(x) => Ok(x.serialize_num()),
+            None => Err("The shared point is at infinity"),
+        }
+    }
+
+    fn shared_point(&self, pubkey: &PublicKey) -> Result<Point, &'static str> {
+        self.check_valid()?;
+        check_valid_point(&pubkey.point)?;
+        let point = ladder::mul_point(&pubkey.point, &self.scalar);
+        if point.is_on_infinity() {
+            return Err("The shared point is at infinity");
+        }
+        Ok(point)
     }
+
     #[allow(clippy::many_single_char_names)]
     pub(crate) fn sign_raw(d: &Integer, k: FieldElement, z: FieldElement) -> Signature {
         let secp = get_context();
-        let k_point: Point = &k.num * secp.generator();
+        let k_point: Point = secp.mul_generator(&k.num);
         let order = &secp.order;
         let mut r = k_point.x;
         r.modulo = order.clone();
@@ -335,6 +536,37 @@ impl PrivateKey {
         SchnorrSignature::new(&r, &s)
     }
 
+    // BIP340 signing with all zeros auxiliary randomness, the message is signed as is (any length, no pre-hashing).
+    pub fn sign_schnorr_msg(&self, msg: &[u8]) -> Result<SchnorrSignature, &'static str> {
+        self.sign_schnorr_msg_with_aux(msg, &[0u8; 32])
+    }
+
+    #[allow(non_snake_case)]
+    pub fn sign_schnorr_msg_with_aux(&self, msg: &[u8], aux_rand: &[u8; 32]) -> Result<SchnorrSignature, &'static str> {
+        self.check_valid()?;
+        let secp = get_context();
+        let order = &secp.order;
+        let P = self.generate_pubkey();
+        let d = if P.has_even_y() { self.scalar.clone() } else { Integer::from(order - &self.scalar) };
+        let mut t = PrivateKey::new(d.clone()).serialize();
+        let aux_hash = tagged_hash(b"BIP0340/aux", aux_rand);
+        t.iter_mut().zip(aux_hash.iter()).for_each(|(t, a)| *t ^= a);
+
+        let rand = tagged_hash(b"BIP0340/nonce", &[&t[..], &P.x_only(), msg].concat());
+        let mut k = FieldElement::from_serialize(&rand, order);
+        k.mod_num();
+        if k.is_zero() {
+            return Err("The nonce is zero");
+        }
+        let R = secp.mul_generator(&k.num);
+        if !R.y.is_even() {
+            k = order - k;
+        }
+        let e = bip340_challenge(&R.x.clone().serialize_num(), &P.x_only(), msg);
+
+        Ok(Self::sign_schnorr_raw(&d, k, e, Some(R)))
+    }
+
     pub fn serialize(&self) -> [u8; 32] {
         let mut res = [0u8; 32];
         let serialized = self.scalar.to_digits(Order::MsfBe);
@@ -354,6 +586,54 @@ impl PrivateKey {
     pub fn negate(self) -> PrivateKey {
         PrivateKey { scalar: &get_context().order - self.scalar }
     }
+
+    // (d + tweak) mod n, like libsecp256k1's `ec_seckey_tweak_add`.
+    // Fails if the key is invalid, the tweak overflows the order or the result is zero.
+    pub fn add_tweak(&self, tweak: &Scalar) -> Result<PrivateKey, &'static str> {
+        self.check_valid()?;
+        let tweak = tweak_to_integer(tweak)?;
+        let scalar = (tweak + &self.scalar) % &get_context().order;
+        if scalar == 0 {
+            return Err("The tweaked private key is zero");
+        }
+        Ok(PrivateKey { scalar })
+    }
+
+    // (d * tweak) mod n, like libsecp256k1's `ec_seckey_tweak_mul`.
+    // Fails if the key is invalid or the tweak is zero or overflows the order.
+    pub fn mul_tweak(&self, tweak: &Scalar) -> Result<PrivateKey, &'static str> {
+        self.check_valid()?;
+        let tweak = tweak_to_integer(tweak)?;
+        if tweak == 0 {
+            return Err("The tweak is zero");
+        }
+        Ok(PrivateKey { scalar: (tweak * &self.scalar) % &get_context().order })
+    }
+
+    fn check_valid(&self) -> Result<(), &'static str> {
+        if self.scalar == 0 || self.scalar >= get_context().order {
+            Err("The private key is not in the range [1, n-1]")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn check_valid_point(point: &Point) -> Result<(), &'static str> {
+    if point.is_on_infinity() || !point.is_on_curve() {
+        Err("The public key is not a valid point")
+    } else {
+        Ok(())
+    }
+}
+
+fn tweak_to_integer(tweak: &Scalar) -> Result<Integer, &'static str> {
+    let tweak = Integer::from_digits(&tweak.0, Order::MsfBe);
+    if tweak >= get_context().order {
+        Err("The tweak overflows the curve order")
+    } else {
+        Ok(tweak)
+    }
 }
 
 #[allow(non_snake_case)]
@@ -367,6 +647,13 @@ fn get_e(xR: FieldElement, pubkey: PublicKey, msg: [u8; 32]) -> FieldElement {
 }
 
 
+// The BIP340 challenge: e = int(hash_BIP0340/challenge(R.x || P.x || m)) mod n
+pub(crate) fn bip340_challenge(rx: &[u8], px: &[u8], msg: &[u8]) -> FieldElement {
+    let e = tagged_hash(b"BIP0340/challenge", &[rx, px, msg].concat());
+    let mut e = FieldElement::from_serialize(&e, &get_context().order);
+    e.mod_num();
+    e
+}
 
 fn get_hashed_message_if(msg: &[u8], to_hash: bool) -> [u8; 32] {
     let mut msg_hash = [0u8; 32];
@@ -536,6 +823,11 @@ pub fn get_context() -> &'static Secp256k1 {
     unsafe { CONTEXT.as_ref().unwrap() }
 }
 
+// A field element from big endian hex, for constants.
+pub(crate) fn fe_from_hex(hex: &str) -> FieldElement {
+    FieldElement::new(Integer::from_str_radix(hex, 16).unwrap(), &get_context().modulo)
+}
+
 impl fmt::Display for PublicKey {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Public: {{ X: {:#X}, Y: {:#X} }}", self.point.x.inner(), self.point.y.inner())
@@ -822,7 +1114,8 @@ impl Mul<Scalar> for &PrivateKey {
 mod test {
 
     use super::*;
-    use crate::test_vectors::{TestMode, TestVector, SCHNORR_VECTORS};
+    use crate::test_vectors::{TestMode, TestVector, BIP340_VECTORS, SCHNORR_VECTORS};
+    use crate::utility::hex_to_bytes;
 
     #[test]
     fn test_compress_pubkey() {
@@ -847,9 +1140,56 @@ mod test {
         let priv_key2 = PrivateKey::new(49_234_078_927_865_834_890_u128);
         let pub_key2 = priv_key2.generate_pubkey();
 
-        let ecdh1 = priv_key1.ecdh(&pub_key2);
-        let ecdh2 = priv_key2.ecdh(&pub_key1);
+        let ecdh1 = priv_key1.ecdh(&pub_key2).unwrap();
+        let ecdh2 = priv_key2.ecdh(&pub_key1).unwrap();
         assert_eq!(ecdh1, ecdh2);
+
+        let shared = priv_key1.generate_pubkey() * Scalar::new(&priv_key2.serialize());
+        assert_eq!(ecdh1[..], shared.compressed().hash_digest()[..]);
+        assert_eq!(priv_key1.ecdh_x_only(&pub_key2).unwrap(), shared.x_only());
+        let uncompressed = priv_key1.ecdh_with_hash(&pub_key2, |x, y| [&[0x04][..], x, y].concat()).unwrap();
+        assert_eq!(uncompressed[..], shared.uncompressed()[..]);
+    }
+
+    #[test]
+    fn test_ecdh_bolt8() {
+        // The ECDHs of the BOLT 8 handshake vectors, which use the libsecp256k1 hash.
+        let e = PrivateKey::from_serialized(&[0x12; 32]);
+        let rs =
+            PublicKey::from_compressed(&hex_to_bytes("028d7500dd4c12685d1f568b4c2b5048e8534b873319f3a8daa612b469132ec7f7")).unwrap();
+        assert_eq!(e.ecdh(&rs).unwrap()[..], hex_to_bytes("1e2fb3c8fe8fb9f262f649f64d26ecf0f2c0a805a767cf02dc2d77a6ef1fdcc3")[..]);
+        let re = PrivateKey::from_serialized(&[0x22; 32]);
+        assert_eq!(
+            re.ecdh(&e.generate_pubkey()).unwrap()[..],
+            hex_to_bytes("c06363d6cc549bcb7913dbb9ac1c33fc1158680c89e972000ecd06b36c472e47")[..]
+        );
+    }
+
+    #[test]
+    fn test_ecdh_from_x() {
+        let privkey = PrivateKey::new(49_234_078_927_865_834_890_u128);
+        let pubkey = PrivateKey::new(8_764_321_234_u128).generate_pubkey();
+        let shared = privkey.ecdh_x_only(&pubkey).unwrap();
+        assert_eq!(privkey.ecdh_from_x(&pubkey.x_only()).unwrap(), shared);
+        assert_eq!(privkey.ecdh_from_x(&pubkey.clone().negate().x_only()).unwrap(), shared);
+
+        // x = 0 isn't on the curve, and p overflows the field.
+        assert!(privkey.ecdh_from_x(&[0; 32]).is_err());
+        let mut p = [0u8; 32];
+        p.copy_from_slice(&hex_to_bytes("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"));
+        assert!(privkey.ecdh_from_x(&p).is_err());
+        assert!(PrivateKey::new(0).ecdh_from_x(&pubkey.x_only()).is_err());
+    }
+
+    #[test]
+    fn test_ecdh_invalid() {
+        let pubkey = PrivateKey::new(3).generate_pubkey();
+        assert!(PrivateKey::new(0).ecdh(&pubkey).is_err());
+        assert!(PrivateKey::new(get_context().order.clone()).ecdh_x_only(&pubkey).is_err());
+        assert!(PrivateKey::new(1).ecdh(&PublicKey::zero_pubkey()).is_err());
+        let mut off_curve = pubkey.clone();
+        off_curve.point.y = off_curve.point.y + 1u8;
+        assert!(PrivateKey::new(1).ecdh(&off_curve).is_err());
     }
 
     #[test]
@@ -925,6 +1265,86 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_sign_verify_schnorr_msg() {
+        let priv_key = PrivateKey::new(532_557_312_u128);
+        let pub_key = priv_key.generate_pubkey();
+
+        for msg in &[&b""[..], b"HODL!", &[0x42; 33][..], &[7u8; 1000][..]] {
+            let sig = priv_key.sign_schnorr_msg(msg).unwrap();
+            assert!(pub_key.verify_schnorr_msg(msg, SchnorrSignature::parse(sig.serialize())));
+            assert!(!pub_key.verify_schnorr_msg(b"Something else", sig));
+        }
+        assert!(PrivateKey::new(0).sign_schnorr_msg(b"HODL!").is_err());
+        assert!(PrivateKey::new(get_context().order.clone()).sign_schnorr_msg_with_aux(b"HODL!", &[1; 32]).is_err());
+    }
+
+    #[test]
+    fn test_bip340_vectors() {
+        for vec in &BIP340_VECTORS {
+            let msg = hex_to_bytes(vec.msg);
+            let sig = hex_to_bytes(vec.sig);
+            if let (Some(sk), Some(aux_rand)) = (vec.sk, vec.aux_rand) {
+                let privkey = PrivateKey::from_serialized(&hex_to_bytes(sk));
+                let mut aux = [0u8; 32];
+                aux.copy_from_slice(&hex_to_bytes(aux_rand));
+                assert_eq!(privkey.generate_pubkey().x_only()[..], hex_to_bytes(vec.pk)[..]);
+                assert_eq!(privkey.sign_schnorr_msg_with_aux(&msg, &aux).unwrap().serialize()[..], sig[..]);
+            }
+            let pubkey = match PublicKey::from_xonly(&hex_to_bytes(vec.pk)) {
+                Ok(k) => k,
+                Err(_) => {
+                    assert!(!vec.verify_result);
+                    continue;
+                }
+            };
+            assert_eq!(vec.verify_result, pubkey.verify_schnorr_msg(&msg, SchnorrSignature::parse_slice(&sig)));
+        }
+    }
+
+    #[test]
+    fn test_tweaks() {
+        let secp = get_context();
+        let privkey = PrivateKey::new(8_764_321_234_u128);
+        let pubkey = privkey.generate_pubkey();
+        let tweak = Scalar::new(&b"tweak".hash_digest());
+
+        let added = privkey.add_tweak(&tweak).unwrap();
+        assert_eq!(added.generate_pubkey(), pubkey.add_exp_tweak(&tweak).unwrap());
+        let multiplied = privkey.mul_tweak(&tweak).unwrap();
+        assert_eq!(multiplied.generate_pubkey(), pubkey.mul_tweak(&tweak).unwrap());
+
+        // Overflowing and zero tweaks.
+        let order = Scalar::new(&secp.serialized_order());
+        assert!(privkey.add_tweak(&order).is_err());
+        assert!(privkey.mul_tweak(&order).is_err());
+        assert!(pubkey.add_exp_tweak(&order).is_err());
+        assert!(pubkey.mul_tweak(&order).is_err());
+        assert!(privkey.mul_tweak(&Scalar::default()).is_err());
+        assert!(pubkey.mul_tweak(&Scalar::default()).is_err());
+        assert_eq!(privkey.add_tweak(&Scalar::default()).unwrap().serialize(), privkey.serialize());
+
+        // Tweaking by -d gives zero / infinity.
+        let minus_d = Scalar::new(&PrivateKey::new(8_764_321_234_u128).negate().serialize());
+        assert!(privkey.add_tweak(&minus_d).is_err());
+        assert!(pubkey.add_exp_tweak(&minus_d).is_err());
+        assert!(PrivateKey::new(0).add_tweak(&tweak).is_err());
+    }
+
+    #[test]
+    fn test_xonly_tweak_add_check() {
+        let tweak = Scalar::new(&b"tweak".hash_digest());
+        for key in &[PrivateKey::new(3), PrivateKey::new(8_764_321_234_u128)] {
+            let internal = key.generate_pubkey();
+            let tweaked = internal.xonly_add_exp_tweak(&tweak).unwrap();
+            let parity = !tweaked.has_even_y();
+            assert!(internal.tweak_add_check(&tweaked.x_only(), parity, &tweak));
+            assert!(internal.clone().negate().tweak_add_check(&tweaked.x_only(), parity, &tweak));
+            assert!(!internal.tweak_add_check(&tweaked.x_only(), !parity, &tweak));
+            assert!(!internal.tweak_add_check(&internal.x_only(), parity, &tweak));
+        }
+    }
+
     #[test]
     fn test_negate_pubkeys() {
         let key = PrivateKey::new(Integer::from(100));
@@ -942,4 +1362,17 @@ mod test {
 
         assert_eq!(neg_pubkey, cal_neg_pubkey);
     }
+
+    #[test]
+    fn test_multi_mul() {
+        let secp = get_context();
+        let terms: Vec<_> =
+            (1..=4u32).map(|i| ((Integer::from(i) << 200) + i, PrivateKey::new(1000 + i).generate_pubkey().into())).collect();
+        let expected = terms.iter().fold(secp.generator().gen_zero(), |acc, (k, p): &(Integer, Point)| acc + k * p.clone());
+        assert_eq!(secp.multi_mul(&terms), expected);
+        assert!(secp.multi_mul(&[]).is_on_infinity());
+        // Scalars are reduced mod n.
+        let p: Point = PrivateKey::new(5).generate_pubkey().into();
+        assert_eq!(secp.multi_mul(&[(Integer::from(&secp.order + 3), p.clone())]), Integer::from(3) * p);
+    }
 }
@@ -0,0 +1,244 @@
+use crate::point::Point;
+use crate::secp256k1::{get_context, PrivateKey, PublicKey};
+use crate::utility::{int_from_bytes, int_to_bytes, scalar_from_seed};
+use bech32::{FromBase32, ToBase32};
+use bitcoin_hashes::{hash160, Hash};
+use rug::Integer;
+
+// Shamir secret sharing of private keys over the scalar field, with Feldman commitments.
+// The key is the constant term of a random polynomial f of degree threshold-1, share i is f(i),
+// and any `threshold` shares recover the key with Lagrange interpolation.
+// The commitments a_j*G to the coefficients let every shareholder check its share without learning the others.
+
+const SHARE_HRP: &str = "keyshare";
+
+// The BIP32 style fingerprint of a public key: the first 4 bytes of hash160 of its compressed serialization.
+pub fn key_fingerprint(pubkey: &PublicKey) -> [u8; 4] {
+    let hash = hash160::Hash::hash(&pubkey.compressed());
+    let mut fingerprint = [0u8; 4];
+    fingerprint.copy_from_slice(&hash[..4]);
+    fingerprint
+}
+
+// A share of a private key. It remembers the threshold and the fingerprint of the key,
+// so a pile of paper backups can be sorted and checked before recovering.
+#[derive(Clone)]
+pub struct KeyShare {
+    index: u8,
+    threshold: u8,
+    fingerprint: [u8; 4],
+    value: Integer,
+}
+
+impl KeyShare {
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    pub fn fingerprint(&self) -> [u8; 4] {
+        self.fingerprint
+    }
+
+    // Feldman verification against the commitments published by the dealer.
+    pub fn verify(&self, commitment: &[PublicKey]) -> bool {
+        commitment.len() == self.threshold as usize
+            && key_fingerprint(&commitment[0]) == self.fingerprint
+            && get_context().mul_generator(&self.value) == eval_commitment(commitment, u32::from(self.index))
+    }
+
+    // index || threshold || fingerprint (4 bytes) || share (32 bytes)
+    pub fn serialize(&self) -> [u8; 38] {
+        let mut res = [0u8; 38];
+        res[0] = self.index;
+        res[1] = self.threshold;
+        res[2..6].copy_from_slice(&self.fingerprint);
+        res[6..].copy_from_slice(&int_to_bytes(&self.value));
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<KeyShare, &'static str> {
+        if ser.len() != 38 {
+            return Err("A key share should be 38 bytes");
+        }
+        if ser[0] == 0 {
+            return Err("A key share index can't be zero");
+        }
+        if ser[1] == 0 {
+            return Err("A key share threshold can't be zero");
+        }
+        let value = int_from_bytes(&ser[6..]);
+        if value >= get_context().order {
+            return Err("The key share overflows the curve order");
+        }
+        let mut fingerprint = [0u8; 4];
+        fingerprint.copy_from_slice(&ser[2..6]);
+        Ok(KeyShare { index: ser[0], threshold: ser[1], fingerprint, value })
+    }
+
+    // The serialization as a bech32 string, the checksum catches typos when it's copied from paper.
+    pub fn to_bech32(&self) -> String {
+        bech32::encode(SHARE_HRP, self.serialize().to_base32()).unwrap()
+    }
+
+    pub fn from_bech32(s: &str) -> Result<KeyShare, &'static str> {
+        let (hrp, data) = bech32::decode(s).map_err(|_| "Invalid bech32 string")?;
+        if hrp != SHARE_HRP {
+            return Err("The string isn't a key share");
+        }
+        KeyShare::parse(&Vec::<u8>::from_base32(&data).map_err(|_| "Invalid bech32 string")?)
+    }
+}
+
+// Split `sk` into `n` shares with a random polynomial, any `threshold` of them recover it.
+// Returns the shares and the Feldman commitments, which can be published.
+pub fn split(sk: &PrivateKey, threshold: u8, n: u8, rand: &[u8; 32]) -> Result<(Vec<KeyShare>, Vec<PublicKey>), &'static str> {
+    let order = &get_context().order;
+    if threshold == 0 || threshold > n {
+        return Err("The threshold must be in the range [1, n]");
+    }
+    if sk.scalar == 0 || sk.scalar >= *order {
+        return Err("The private key is not in the range [1, n-1]");
+    }
+    let seed = [&rand[..], &sk.serialize()].concat();
+    let mut coefficients = vec![sk.scalar.clone()];
+    coefficients.extend((1..threshold).map(|j| scalar_from_seed(b"Shamir/coefficient", &[&seed[..], &[j]].concat())));
+    let commitment = commit_polynomial(&coefficients);
+
+    let fingerprint = key_fingerprint(&commitment[0]);
+    let shares = (1..=n)
+        .map(|index| KeyShare { index, threshold, fingerprint, value: eval_polynomial(&coefficients, u32::from(index)) })
+        .collect();
+    Ok((shares, commitment))
+}
+
+// Recover the key from at least `threshold` shares of it.
+// Fails if the shares don't belong together, or if the result doesn't match their fingerprint (a share is corrupted).
+pub fn combine(shares: &[KeyShare]) -> Result<PrivateKey, &'static str> {
+    let order = &get_context().order;
+    let first = shares.first().ok_or("There are no shares to combine")?;
+    if shares.iter().any(|share| share.threshold != first.threshold || share.fingerprint != first.fingerprint) {
+        return Err("The shares are from different keys");
+    }
+    let mut indices: Vec<_> = shares.iter().map(|share| u32::from(share.index)).collect();
+    if indices.len() < first.threshold as usize {
+        return Err("Not enough shares to recover the key");
+    }
+    let signers = indices.clone();
+    indices.sort_unstable();
+    indices.dedup();
+    if indices.len() != shares.len() {
+        return Err("A share appears twice");
+    }
+
+    let scalar = shares
+        .iter()
+        .map(|share| lagrange_coefficient(&signers, u32::from(share.index)) * &share.value)
+        .fold(Integer::new(), |acc, term| (acc + term) % order);
+    let sk = PrivateKey::new(scalar);
+    if sk.scalar == 0 || key_fingerprint(&sk.generate_pubkey()) != first.fingerprint {
+        return Err("The shares don't recover the key they were made from");
+    }
+    Ok(sk)
+}
+
+// lambda_i = prod(j / (j - i)) over the other indices j, the Lagrange coefficient for interpolating f(0).
+// The indices must be distinct.
+pub(crate) fn lagrange_coefficient(indices: &[u32], index: u32) -> Integer {
+    let order = &get_context().order;
+    let (num, den) = indices.iter().filter(|j| **j != index).fold((Integer::from(1), Integer::from(1)), |(num, den), j| {
+        let diff = (Integer::from(*j) - index) % order;
+        ((num * *j) % order, (den * diff) % order)
+    });
+    let den = if den < 0 { den + order } else { den };
+    (num * den.invert(order).unwrap()) % order
+}
+
+// f(x) = a_0 + a_1*x + ... + a_(t-1)*x^(t-1) mod n, using Horner's method.
+pub(crate) fn eval_polynomial(coefficients: &[Integer], x: u32) -> Integer {
+    let order = &get_context().order;
+    coefficients.iter().rev().fold(Integer::new(), |acc, a| (acc * x + a) % order)
+}
+
+pub(crate) fn commit_polynomial(coefficients: &[Integer]) -> Vec<PublicKey> {
+    coefficients.iter().map(|a| get_context().mul_generator(a).into()).collect()
+}
+
+// The commitment to f(x): C_0 + x*C_1 + ... + x^(t-1)*C_(t-1), using Horner's method.
+pub(crate) fn eval_commitment(commitment: &[PublicKey], x: u32) -> Point {
+    let x = Integer::from(x);
+    commitment.iter().rev().fold(Point::from(PublicKey::zero_pubkey()), |acc, c| {
+        let acc = if acc.is_on_infinity() { acc } else { &x * acc };
+        acc + Point::from(c.clone())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_combine() {
+        let sk = PrivateKey::from_serialized(&[0x11; 32]);
+        let (shares, commitment) = split(&sk, 3, 5, &[7; 32]).unwrap();
+        assert_eq!(commitment.len(), 3);
+        assert_eq!(commitment[0], sk.generate_pubkey());
+        assert!(shares.iter().all(|share| share.verify(&commitment)));
+
+        for indices in &[[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset: Vec<_> = indices.iter().map(|i| shares[*i].clone()).collect();
+            assert_eq!(combine(&subset).unwrap().serialize(), sk.serialize());
+        }
+        assert_eq!(combine(&shares).unwrap().serialize(), sk.serialize());
+        assert_eq!(combine(&shares[..2]).err(), Some("Not enough shares to recover the key"));
+        assert_eq!(combine(&[shares[0].clone(), shares[0].clone(), shares[1].clone()]).err(), Some("A share appears twice"));
+        assert!(combine(&[]).is_err());
+
+        let (one, _) = split(&sk, 1, 1, &[7; 32]).unwrap();
+        assert_eq!(one[0].value, sk.scalar);
+        assert!(split(&sk, 0, 5, &[7; 32]).is_err());
+        assert!(split(&sk, 6, 5, &[7; 32]).is_err());
+    }
+
+    #[test]
+    fn test_corrupted_shares() {
+        let sk = PrivateKey::from_serialized(&[0x22; 32]);
+        let (mut shares, commitment) = split(&sk, 2, 3, &[8; 32]).unwrap();
+        shares[1].value += 1;
+        assert!(shares[0].verify(&commitment));
+        assert!(!shares[1].verify(&commitment));
+        assert_eq!(combine(&shares[..2]).err(), Some("The shares don't recover the key they were made from"));
+        assert!(!shares[0].verify(&commitment[..1]));
+
+        let (other, other_commitment) = split(&PrivateKey::from_serialized(&[0x33; 32]), 2, 3, &[8; 32]).unwrap();
+        assert!(!shares[0].verify(&other_commitment));
+        assert_eq!(combine(&[shares[0].clone(), other[1].clone()]).err(), Some("The shares are from different keys"));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let sk = PrivateKey::from_serialized(&[0x44; 32]);
+        let (shares, commitment) = split(&sk, 2, 3, &[9; 32]).unwrap();
+        let ser = shares[2].serialize();
+        assert_eq!(ser[..6], [3, 2, ser[2], ser[3], ser[4], ser[5]]);
+        assert_eq!(ser[2..6], key_fingerprint(&sk.generate_pubkey()));
+
+        let encoded: Vec<_> = shares.iter().map(KeyShare::to_bech32).collect();
+        assert!(encoded.iter().all(|s| s.starts_with("keyshare1")));
+        let decoded: Vec<_> = encoded.iter().map(|s| KeyShare::from_bech32(s).unwrap()).collect();
+        assert!(decoded.iter().all(|share| share.verify(&commitment)));
+        assert_eq!(combine(&decoded[1..]).unwrap().serialize(), sk.serialize());
+
+        // A typo breaks the checksum.
+        let mut typo = encoded[0].clone().into_bytes();
+        typo[20] = if typo[20] == b'q' { b'p' } else { b'q' };
+        assert!(KeyShare::from_bech32(&String::from_utf8(typo).unwrap()).is_err());
+        assert!(KeyShare::from_bech32(&bech32::encode("bc", ser.to_base32()).unwrap()).is_err());
+        assert!(KeyShare::parse(&ser[1..]).is_err());
+        assert!(KeyShare::parse(&[&[0u8][..], &ser[1..]].concat()).is_err());
+        assert!(KeyShare::parse(&[&ser[..6], &[0xff; 32][..]].concat()).is_err());
+    }
+}
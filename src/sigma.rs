@@ -0,0 +1,329 @@
+use crate::point::Point;
+use crate::secp256k1::{get_context, PrivateKey, PublicKey};
+use crate::transcript::Transcript;
+use crate::utility::{int_to_bytes, parse_scalar, scalar_from_seed};
+use rug::Integer;
+
+// Schnorr proofs of knowledge of discrete logs, made non-interactive with a `Transcript`, and their compositions:
+// `AndProof` proves knowledge of all the keys, `OrProof` proves knowledge of one of them without revealing which.
+// The proofs absorb the public keys themselves, the caller adds whatever else the proof should be bound to
+// (who is registering the key, a session id...). Prover and verifier must absorb the same data in the same order.
+
+// The label of the challenge in the transcript.
+const CHALLENGE_LABEL: &[u8] = b"challenge";
+
+// A proof of knowledge of x such that P = x*G.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchnorrProof {
+    r: PublicKey,
+    s: Integer,
+}
+
+impl SchnorrProof {
+    // `rand` must be fresh randomness.
+    pub fn prove(transcript: &mut Transcript, sk: &PrivateKey, rand: &[u8; 32]) -> Result<SchnorrProof, &'static str> {
+        let AndProof(mut proofs) = AndProof::prove(transcript, &[sk], rand)?;
+        Ok(proofs.remove(0))
+    }
+
+    pub fn verify(&self, transcript: &mut Transcript, pubkey: &PublicKey) -> bool {
+        AndProof(vec![self.clone()]).verify(transcript, std::slice::from_ref(pubkey))
+    }
+
+    // R (33 bytes) || s (32 bytes)
+    pub fn serialize(&self) -> [u8; 65] {
+        let mut res = [0u8; 65];
+        res[..33].copy_from_slice(&self.r.compressed());
+        res[33..].copy_from_slice(&int_to_bytes(&self.s));
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<SchnorrProof, &'static str> {
+        if ser.len() != 65 {
+            return Err("A Schnorr proof should be 65 bytes");
+        }
+        let r = PublicKey::from_compressed(&ser[..33])?;
+        let s = parse_scalar(&ser[33..])?;
+        Ok(SchnorrProof { r, s })
+    }
+}
+
+// A proof of possession of the private key, for registering keys before they get aggregated (rogue key attacks).
+// `context` should identify the registration, e.g. the account the key is registered for.
+pub fn prove_possession(sk: &PrivateKey, context: &[u8], rand: &[u8; 32]) -> Result<SchnorrProof, &'static str> {
+    SchnorrProof::prove(&mut possession_transcript(context), sk, rand)
+}
+
+pub fn verify_possession(proof: &SchnorrProof, pubkey: &PublicKey, context: &[u8]) -> bool {
+    proof.verify(&mut possession_transcript(context), pubkey)
+}
+
+fn possession_transcript(context: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(b"SchnorrPoK/possession");
+    transcript.append_message(b"context", context);
+    transcript
+}
+
+// Proofs of knowledge of all the keys, with a single challenge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AndProof(Vec<SchnorrProof>);
+
+impl AndProof {
+    // `rand` must be fresh randomness.
+    pub fn prove(transcript: &mut Transcript, sks: &[&PrivateKey], rand: &[u8; 32]) -> Result<AndProof, &'static str> {
+        let secp = get_context();
+        let order = &secp.order;
+        if sks.is_empty() {
+            return Err("There are no keys to prove");
+        }
+        if sks.iter().any(|sk| sk.scalar == 0 || sk.scalar >= *order) {
+            return Err("The private key is not in the range [1, n-1]");
+        }
+        transcript.append_message(b"proof", b"and");
+        sks.iter().for_each(|sk| transcript.append_point(b"pubkey", &sk.generate_pubkey()));
+
+        let seed = [&rand[..], &transcript.state()].concat();
+        let nonces: Vec<_> = sks.iter().enumerate().map(|(i, sk)| proof_nonce(&seed, i, &int_to_bytes(&sk.scalar))).collect();
+        let commitments: Vec<_> = nonces.iter().map(|k| PublicKey::from(secp.mul_generator(k))).collect();
+        commitments.iter().for_each(|r| transcript.append_point(b"R", r));
+        let e = transcript.challenge_scalar(CHALLENGE_LABEL);
+
+        let proofs = nonces
+            .into_iter()
+            .zip(commitments)
+            .zip(sks)
+            .map(|((k, r), sk)| SchnorrProof { r, s: (k + Integer::from(&e * &sk.scalar)) % order })
+            .collect();
+        Ok(AndProof(proofs))
+    }
+
+    pub fn verify(&self, transcript: &mut Transcript, pubkeys: &[PublicKey]) -> bool {
+        if self.0.is_empty() || self.0.len() != pubkeys.len() || any_infinity(pubkeys) {
+            return false;
+        }
+        transcript.append_message(b"proof", b"and");
+        pubkeys.iter().for_each(|pubkey| transcript.append_point(b"pubkey", pubkey));
+        self.0.iter().for_each(|proof| transcript.append_point(b"R", &proof.r));
+        let e = transcript.challenge_scalar(CHALLENGE_LABEL);
+        self.0.iter().zip(pubkeys).all(|(proof, pubkey)| commitment(pubkey, &e, &proof.s) == Point::from(proof.r.clone()))
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        self.0.iter().flat_map(|proof| proof.serialize().to_vec()).collect()
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<AndProof, &'static str> {
+        let chunks = ser.chunks_exact(65);
+        if ser.is_empty() || !chunks.remainder().is_empty() {
+            return Err("An AND proof should be a multiple of 65 bytes");
+        }
+        Ok(AndProof(chunks.map(SchnorrProof::parse).collect::<Result<_, _>>()?))
+    }
+}
+
+// A proof of knowledge of one of the keys (Cramer, Damgard, Schoenmakers). The prover simulates the proofs for the
+// keys it doesn't know by picking their challenges, the challenges have to sum up to the transcript challenge
+// so at least one proof is real.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrProof {
+    // (challenge, response) per key.
+    proofs: Vec<(Integer, Integer)>,
+}
+
+impl OrProof {
+    // Prove knowledge of the key `pubkeys[index]`, which `sk` is the private key of. `rand` must be fresh randomness.
+    pub fn prove(
+        transcript: &mut Transcript,
+        pubkeys: &[PublicKey],
+        index: usize,
+        sk: &PrivateKey,
+        rand: &[u8; 32],
+    ) -> Result<OrProof, &'static str> {
+        let secp = get_context();
+        let order = &secp.order;
+        if index >= pubkeys.len() || sk.generate_pubkey() != pubkeys[index] {
+            return Err("The private key doesn't match the key at the index");
+        }
+        transcript.append_message(b"proof", b"or");
+        pubkeys.iter().for_each(|pubkey| transcript.append_point(b"pubkey", pubkey));
+
+        let seed = [&rand[..], &transcript.state(), &int_to_bytes(&sk.scalar)].concat();
+        let mut proofs: Vec<_> = (0..pubkeys.len())
+            .map(|i| (proof_nonce(&seed, 2 * i, b"challenge"), proof_nonce(&seed, 2 * i + 1, b"response")))
+            .collect();
+        // The real proof starts with a nonce, its challenge is only known once all the commitments are absorbed.
+        let k = proofs[index].1.clone();
+        for (i, (pubkey, (e, s))) in pubkeys.iter().zip(&proofs).enumerate() {
+            let r = if i == index { secp.mul_generator(&k) } else { commitment(pubkey, e, s) };
+            if r.is_on_infinity() {
+                return Err("A commitment is the point at infinity");
+            }
+            transcript.append_point(b"R", &r.into());
+        }
+        let e = transcript.challenge_scalar(CHALLENGE_LABEL);
+
+        let others = proofs.iter().enumerate().filter(|(i, _)| *i != index).fold(Integer::new(), |acc, (_, (e, _))| (acc + e) % order);
+        let e = (e + order - others) % order;
+        let s = (k + Integer::from(&e * &sk.scalar)) % order;
+        proofs[index] = (e, s);
+        Ok(OrProof { proofs })
+    }
+
+    pub fn verify(&self, transcript: &mut Transcript, pubkeys: &[PublicKey]) -> bool {
+        let order = &get_context().order;
+        if self.proofs.is_empty() || self.proofs.len() != pubkeys.len() || any_infinity(pubkeys) {
+            return false;
+        }
+        transcript.append_message(b"proof", b"or");
+        pubkeys.iter().for_each(|pubkey| transcript.append_point(b"pubkey", pubkey));
+        for (pubkey, (e, s)) in pubkeys.iter().zip(&self.proofs) {
+            let r = commitment(pubkey, e, s);
+            if r.is_on_infinity() {
+                return false;
+            }
+            transcript.append_point(b"R", &r.into());
+        }
+        let e = transcript.challenge_scalar(CHALLENGE_LABEL);
+        self.proofs.iter().fold(Integer::new(), |acc, (e, _)| (acc + e) % order) == e
+    }
+
+    // challenge (32 bytes) || response (32 bytes) per key.
+    pub fn serialize(&self) -> Vec<u8> {
+        self.proofs.iter().flat_map(|(e, s)| [int_to_bytes(e), int_to_bytes(s)].concat()).collect()
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<OrProof, &'static str> {
+        let chunks = ser.chunks_exact(64);
+        if ser.is_empty() || !chunks.remainder().is_empty() {
+            return Err("An OR proof should be a multiple of 64 bytes");
+        }
+        let proofs =
+            ser.chunks(64).map(|proof| Ok((parse_scalar(&proof[..32])?, parse_scalar(&proof[32..])?))).collect::<Result<_, _>>()?;
+        Ok(OrProof { proofs })
+    }
+}
+
+// The point at infinity is nobody's key, anyone can "prove" knowledge of its discrete log 0.
+fn any_infinity(pubkeys: &[PublicKey]) -> bool {
+    pubkeys.iter().any(|pubkey| Point::from(pubkey.clone()).is_on_infinity())
+}
+
+// s*G - e*P, the commitment R a valid proof with challenge e and response s has.
+fn commitment(pubkey: &PublicKey, e: &Integer, s: &Integer) -> Point {
+    let secp = get_context();
+    secp.mul_generator(s) + Integer::from(&secp.order - e) * Point::from(pubkey.clone())
+}
+
+// A scalar in [1, n-1] derived from the seed, the index and extra data.
+fn proof_nonce(seed: &[u8], i: usize, extra: &[u8]) -> Integer {
+    scalar_from_seed(b"SchnorrPoK/nonce", &[seed, &(i as u64).to_be_bytes(), extra].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(n: u8) -> (Vec<PrivateKey>, Vec<PublicKey>) {
+        let sks: Vec<_> = (1..=n).map(|i| PrivateKey::from_serialized(&[i; 32])).collect();
+        let pubkeys = sks.iter().map(PrivateKey::generate_pubkey).collect();
+        (sks, pubkeys)
+    }
+
+    #[test]
+    fn test_schnorr_proof() {
+        let (sks, pubkeys) = keys(2);
+        let proof = prove_possession(&sks[0], b"alice", &[1; 32]).unwrap();
+        assert!(verify_possession(&proof, &pubkeys[0], b"alice"));
+        assert!(!verify_possession(&proof, &pubkeys[0], b"bob"));
+        assert!(!verify_possession(&proof, &pubkeys[1], b"alice"));
+        assert_eq!(SchnorrProof::parse(&proof.serialize()).unwrap(), proof);
+
+        // The proof is bound to everything absorbed before it.
+        let mut transcript = Transcript::new(b"test");
+        transcript.append_message(b"session", b"1");
+        let proof = SchnorrProof::prove(&mut transcript.clone(), &sks[1], &[2; 32]).unwrap();
+        assert!(proof.verify(&mut transcript.clone(), &pubkeys[1]));
+        assert!(!proof.verify(&mut Transcript::new(b"test"), &pubkeys[1]));
+        let mut tampered = proof.clone();
+        tampered.s += 1;
+        assert!(!tampered.verify(&mut transcript.clone(), &pubkeys[1]));
+
+        assert!(SchnorrProof::parse(&proof.serialize()[1..]).is_err());
+        assert!(SchnorrProof::parse(&[&proof.serialize()[..33], &[0xff; 32][..]].concat()).is_err());
+        assert!(prove_possession(&PrivateKey::new(0), b"alice", &[1; 32]).is_err());
+    }
+
+    #[test]
+    fn test_and_proof() {
+        let (sks, pubkeys) = keys(3);
+        let transcript = Transcript::new(b"test");
+        let proof = AndProof::prove(&mut transcript.clone(), &sks.iter().collect::<Vec<_>>(), &[3; 32]).unwrap();
+        assert!(proof.verify(&mut transcript.clone(), &pubkeys));
+        assert_eq!(AndProof::parse(&proof.serialize()).unwrap(), proof);
+
+        assert!(!proof.verify(&mut transcript.clone(), &pubkeys[..2]));
+        assert!(!proof.verify(&mut transcript.clone(), &[pubkeys[1].clone(), pubkeys[0].clone(), pubkeys[2].clone()]));
+        // A proof for a subset doesn't prove all the keys.
+        let partial = AndProof::prove(&mut transcript.clone(), &[&sks[0], &sks[1]], &[3; 32]).unwrap();
+        let mut proofs = partial.0.clone();
+        proofs.push(proof.0[2].clone());
+        assert!(!AndProof(proofs).verify(&mut transcript.clone(), &pubkeys));
+        assert!(AndProof::prove(&mut transcript.clone(), &[], &[3; 32]).is_err());
+        assert!(AndProof::parse(&[]).is_err());
+    }
+
+    #[test]
+    fn test_or_proof() {
+        let (sks, pubkeys) = keys(4);
+        let transcript = Transcript::new(b"test");
+        let proofs: Vec<_> =
+            (0..4).map(|i| OrProof::prove(&mut transcript.clone(), &pubkeys, i, &sks[i], &[i as u8; 32]).unwrap()).collect();
+        for proof in &proofs {
+            assert!(proof.verify(&mut transcript.clone(), &pubkeys));
+            assert_eq!(OrProof::parse(&proof.serialize()).unwrap(), *proof);
+            assert!(!proof.verify(&mut Transcript::new(b"other"), &pubkeys));
+            assert!(!proof.verify(&mut transcript.clone(), &pubkeys[1..]));
+        }
+        // A single key is a plain proof of knowledge.
+        let single = OrProof::prove(&mut transcript.clone(), &pubkeys[2..3], 0, &sks[2], &[5; 32]).unwrap();
+        assert!(single.verify(&mut transcript.clone(), &pubkeys[2..3]));
+
+        // Without any of the private keys there's no proof.
+        let outsider = PrivateKey::from_serialized(&[9; 32]);
+        assert!(OrProof::prove(&mut transcript.clone(), &pubkeys, 0, &outsider, &[6; 32]).is_err());
+        assert!(OrProof::prove(&mut transcript.clone(), &pubkeys, 4, &sks[0], &[6; 32]).is_err());
+        let mut tampered = proofs[0].clone();
+        tampered.proofs[1].0 += 1;
+        assert!(!tampered.verify(&mut transcript.clone(), &pubkeys));
+        assert!(OrProof::parse(&proofs[0].serialize()[1..]).is_err());
+    }
+
+    #[test]
+    fn test_infinity_pubkey() {
+        let secp = get_context();
+        let (_, pubkeys) = keys(1);
+        let infinity = PublicKey::zero_pubkey();
+        let transcript = Transcript::new(b"test");
+
+        // With P = 0, any s gives the valid commitment R = s*G.
+        let s = Integer::from(7);
+        let forged = SchnorrProof { r: secp.mul_generator(&s).into(), s };
+        assert_eq!(commitment(&infinity, &Integer::from(1), &forged.s), Point::from(forged.r.clone()));
+        assert!(!forged.verify(&mut transcript.clone(), &infinity));
+        assert!(!verify_possession(&forged, &infinity, b"alice"));
+        assert!(!AndProof(vec![forged.clone(), forged]).verify(&mut transcript.clone(), &[infinity.clone(), infinity.clone()]));
+
+        // The challenge and response of the infinity key are free, the OR proof would verify without any private key.
+        let with_infinity = [pubkeys[0].clone(), infinity];
+        let e = Integer::from(1);
+        let mut forged = transcript.clone();
+        forged.append_message(b"proof", b"or");
+        with_infinity.iter().for_each(|pubkey| forged.append_point(b"pubkey", pubkey));
+        forged.append_point(b"R", &commitment(&pubkeys[0], &e, &Integer::from(2)).into());
+        forged.append_point(b"R", &secp.mul_generator(&Integer::from(3)).into());
+        let challenge = forged.challenge_scalar(CHALLENGE_LABEL);
+        let forged =
+            OrProof { proofs: vec![(e, Integer::from(2)), ((challenge + &secp.order - 1u32) % &secp.order, Integer::from(3))] };
+        assert!(!forged.verify(&mut transcript.clone(), &with_infinity));
+    }
+}
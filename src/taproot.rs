@@ -0,0 +1,600 @@
+use crate::script::Script;
+use crate::secp256k1::{PrivateKey, PublicKey, Scalar, SchnorrSignature};
+use crate::utility::tagged_hash;
+
+// BIP341 tweak: t = hash_TapTweak(P.x || merkle_root).
+// Without a script tree the output key only commits to the internal key: t = hash_TapTweak(P.x)
+pub fn tap_tweak_hash(internal_key: &PublicKey, merkle_root: Option<&[u8; 32]>) -> Scalar {
+    let mut data = internal_key.x_only().to_vec();
+    if let Some(root) = merkle_root {
+        data.extend_from_slice(root);
+    }
+    Scalar(tagged_hash(b"TapTweak", &data))
+}
+
+impl PublicKey {
+    // The taproot output key Q = P + t*G, where P is the x-only internal key (self).
+    // The parity bit a spender needs is `!Q.has_even_y()`.
+    pub fn tap_tweak(&self, merkle_root: Option<&[u8; 32]>) -> Result<PublicKey, &'static str> {
+        self.xonly_add_exp_tweak(&tap_tweak_hash(self, merkle_root))
+    }
+
+    // Check that the output key (x-only + parity) commits to this internal key and the script tree root.
+    pub fn verify_tap_commitment(&self, output_key: &[u8; 32], output_parity: bool, merkle_root: Option<&[u8; 32]>) -> bool {
+        self.tweak_add_check(output_key, output_parity, &tap_tweak_hash(self, merkle_root))
+    }
+}
+
+impl PrivateKey {
+    // The secret key of the taproot output key.
+    // The internal key is negated first if its public key has an odd y (BIP341 `taproot_tweak_seckey`).
+    pub fn tap_tweak(&self, merkle_root: Option<&[u8; 32]>) -> Result<PrivateKey, &'static str> {
+        let internal_key = self.generate_pubkey();
+        let key = PrivateKey::new(self.scalar.clone());
+        let key = if internal_key.has_even_y() { key } else { key.negate() };
+        key.add_tweak(&tap_tweak_hash(&internal_key, merkle_root))
+    }
+
+    // BIP341 key path signing, a BIP340 signature with the tweaked secret key.
+    pub fn sign_taproot(&self, msg: &[u8], merkle_root: Option<&[u8; 32]>) -> Result<SchnorrSignature, &'static str> {
+        self.sign_taproot_with_aux(msg, merkle_root, &[0u8; 32])
+    }
+
+    pub fn sign_taproot_with_aux(
+        &self,
+        msg: &[u8],
+        merkle_root: Option<&[u8; 32]>,
+        aux_rand: &[u8; 32],
+    ) -> Result<SchnorrSignature, &'static str> {
+        self.tap_tweak(merkle_root)?.sign_schnorr_msg_with_aux(msg, aux_rand)
+    }
+}
+
+// The leaf version of BIP342 tapscript.
+pub const TAPROOT_LEAF_TAPSCRIPT: u8 = 0xc0;
+// A control block is the leaf version / parity byte and the internal key followed by up to 128 hashes.
+pub const TAPROOT_CONTROL_BASE_SIZE: usize = 33;
+pub const TAPROOT_CONTROL_NODE_SIZE: usize = 32;
+pub const TAPROOT_CONTROL_MAX_NODE_COUNT: usize = 128;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TapLeaf {
+    pub script: Script,
+    pub version: u8,
+}
+
+impl TapLeaf {
+    pub fn new(script: Script) -> TapLeaf {
+        TapLeaf { script, version: TAPROOT_LEAF_TAPSCRIPT }
+    }
+
+    // The lowest bit of the first control block byte is the parity, so leaf versions are even.
+    // 0x50 is excluded as it would be ambiguous with the annex.
+    pub fn with_version(script: Script, version: u8) -> Result<TapLeaf, &'static str> {
+        if version & 1 != 0 || version == 0x50 {
+            return Err("Invalid leaf version");
+        }
+        Ok(TapLeaf { script, version })
+    }
+
+    // hash_TapLeaf(version || compact_size(script length) || script)
+    pub fn leaf_hash(&self) -> [u8; 32] {
+        let script = self.script.as_bytes();
+        let mut data = vec![self.version];
+        write_compact_size(&mut data, script.len());
+        data.extend_from_slice(script);
+        tagged_hash(b"TapLeaf", &data)
+    }
+}
+
+fn write_compact_size(data: &mut Vec<u8>, len: usize) {
+    match len {
+        0..=0xfc => data.push(len as u8),
+        0xfd..=0xffff => {
+            data.push(0xfd);
+            data.extend_from_slice(&(len as u16).to_le_bytes());
+        }
+        0x10000..=0xffff_ffff => {
+            data.push(0xfe);
+            data.extend_from_slice(&(len as u32).to_le_bytes());
+        }
+        _ => {
+            data.push(0xff);
+            data.extend_from_slice(&(len as u64).to_le_bytes());
+        }
+    }
+}
+
+// hash_TapBranch of the two children, sorted so the spender doesn't need to tell left from right.
+pub fn tap_branch_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut data = Vec::with_capacity(64);
+    if a < b {
+        data.extend_from_slice(a);
+        data.extend_from_slice(b);
+    } else {
+        data.extend_from_slice(b);
+        data.extend_from_slice(a);
+    }
+    tagged_hash(b"TapBranch", &data)
+}
+
+// A subtree, with the leaves under it and each leaf's merkle path up to this node.
+struct NodeInfo {
+    hash: [u8; 32],
+    leaves: Vec<(TapLeaf, Vec<[u8; 32]>)>,
+}
+
+impl NodeInfo {
+    fn leaf(leaf: TapLeaf) -> NodeInfo {
+        NodeInfo { hash: leaf.leaf_hash(), leaves: vec![(leaf, Vec::new())] }
+    }
+
+    fn combine(mut a: NodeInfo, b: NodeInfo) -> Result<NodeInfo, &'static str> {
+        for (_, path) in a.leaves.iter_mut() {
+            path.push(b.hash);
+        }
+        let a_hash = a.hash;
+        a.leaves.extend(b.leaves.into_iter().map(|(leaf, mut path)| {
+            path.push(a_hash);
+            (leaf, path)
+        }));
+        if a.leaves.iter().any(|(_, path)| path.len() > TAPROOT_CONTROL_MAX_NODE_COUNT) {
+            return Err("Script tree is too deep");
+        }
+        Ok(NodeInfo { hash: tap_branch_hash(&a_hash, &b.hash), leaves: a.leaves })
+    }
+}
+
+// Builds a script tree, either from leaves with explicit depths given in depth first order
+// (like a BIP386 `tr()` descriptor) or as a huffman tree from leaf weights.
+// `branch[d]` holds the unfinished node at depth d waiting for its sibling.
+#[derive(Default)]
+pub struct TaprootBuilder {
+    branch: Vec<Option<NodeInfo>>,
+}
+
+impl TaprootBuilder {
+    pub fn new() -> TaprootBuilder {
+        TaprootBuilder { branch: Vec::new() }
+    }
+
+    // Leaves with higher weights end up closer to the root, so they are cheaper to spend.
+    pub fn with_huffman_tree(leaves: Vec<(u32, TapLeaf)>) -> Result<TaprootBuilder, &'static str> {
+        let mut nodes: Vec<(u64, NodeInfo)> =
+            leaves.into_iter().map(|(weight, leaf)| (u64::from(weight), NodeInfo::leaf(leaf))).collect();
+        if nodes.is_empty() {
+            return Err("Empty script tree");
+        }
+        while nodes.len() > 1 {
+            // Combine the two lightest nodes.
+            nodes.sort_by_key(|(weight, _)| std::cmp::Reverse(*weight));
+            let (weight_a, a) = nodes.pop().unwrap();
+            let (weight_b, b) = nodes.pop().unwrap();
+            nodes.push((weight_a + weight_b, NodeInfo::combine(a, b)?));
+        }
+        let (_, root) = nodes.pop().unwrap();
+        Ok(TaprootBuilder { branch: vec![Some(root)] })
+    }
+
+    pub fn add_leaf(self, depth: u8, script: Script) -> Result<TaprootBuilder, &'static str> {
+        self.add_leaf_with_version(depth, TapLeaf::new(script))
+    }
+
+    pub fn add_leaf_with_version(mut self, depth: u8, leaf: TapLeaf) -> Result<TaprootBuilder, &'static str> {
+        let mut depth = depth as usize;
+        if depth > TAPROOT_CONTROL_MAX_NODE_COUNT {
+            return Err("Script tree is too deep");
+        }
+        if self.is_finalizable() {
+            return Err("Script tree is already complete");
+        }
+        // A shallower leaf can't come before the deeper branch next to it is complete.
+        if depth + 1 < self.branch.len() {
+            return Err("Leaves are not in depth first order");
+        }
+        let mut node = NodeInfo::leaf(leaf);
+        while self.branch.len() == depth + 1 {
+            let sibling = match self.branch.pop() {
+                Some(Some(sibling)) => sibling,
+                _ => break,
+            };
+            node = NodeInfo::combine(sibling, node)?;
+            depth -= 1;
+        }
+        self.branch.resize_with(depth + 1, || None);
+        self.branch[depth] = Some(node);
+        Ok(self)
+    }
+
+    pub fn is_finalizable(&self) -> bool {
+        self.branch.len() == 1 && self.branch[0].is_some()
+    }
+
+    pub fn finalize(mut self, internal_key: &PublicKey) -> Result<TaprootSpendInfo, &'static str> {
+        if !self.is_finalizable() {
+            return Err("Incomplete script tree");
+        }
+        let root = self.branch.pop().unwrap().unwrap();
+        TaprootSpendInfo::new(internal_key, Some(root))
+    }
+}
+
+// Everything needed to spend a taproot output, by key path or by any of the script leaves.
+pub struct TaprootSpendInfo {
+    pub internal_key: PublicKey,
+    pub merkle_root: Option<[u8; 32]>,
+    pub output_key: PublicKey,
+    leaves: Vec<(TapLeaf, Vec<[u8; 32]>)>,
+}
+
+impl TaprootSpendInfo {
+    // An output that can only be spent by the key path.
+    pub fn key_path_only(internal_key: &PublicKey) -> Result<TaprootSpendInfo, &'static str> {
+        TaprootSpendInfo::new(internal_key, None)
+    }
+
+    fn new(internal_key: &PublicKey, root: Option<NodeInfo>) -> Result<TaprootSpendInfo, &'static str> {
+        let internal_key = PublicKey::from_xonly(&internal_key.x_only())?;
+        let merkle_root = root.as_ref().map(|node| node.hash);
+        let output_key = internal_key.tap_tweak(merkle_root.as_ref())?;
+        let leaves = root.map(|node| node.leaves).unwrap_or_default();
+        Ok(TaprootSpendInfo { internal_key, merkle_root, output_key, leaves })
+    }
+
+    // True if the output key has an odd y.
+    pub fn output_key_parity(&self) -> bool {
+        !self.output_key.has_even_y()
+    }
+
+    // The leaves in the order they were added (depth first for explicit depths).
+    pub fn leaves(&self) -> impl Iterator<Item = &TapLeaf> {
+        self.leaves.iter().map(|(leaf, _)| leaf)
+    }
+
+    pub fn control_block(&self, leaf: &TapLeaf) -> Option<ControlBlock> {
+        self.leaves.iter().find(|(l, _)| l == leaf).map(|(leaf, path)| ControlBlock {
+            leaf_version: leaf.version,
+            output_key_parity: self.output_key_parity(),
+            internal_key: self.internal_key.clone(),
+            merkle_branch: path.clone(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ControlBlock {
+    pub leaf_version: u8,
+    pub output_key_parity: bool,
+    pub internal_key: PublicKey,
+    pub merkle_branch: Vec<[u8; 32]>,
+}
+
+impl ControlBlock {
+    // (leaf version | parity) || internal key x || merkle branch
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(TAPROOT_CONTROL_BASE_SIZE + TAPROOT_CONTROL_NODE_SIZE * self.merkle_branch.len());
+        res.push(self.leaf_version | self.output_key_parity as u8);
+        res.extend_from_slice(&self.internal_key.x_only());
+        for node in &self.merkle_branch {
+            res.extend_from_slice(node);
+        }
+        res
+    }
+
+    pub fn parse(ser: &[u8]) -> Result<ControlBlock, &'static str> {
+        if ser.len() < TAPROOT_CONTROL_BASE_SIZE {
+            return Err("Invalid control block size");
+        }
+        let nodes = ser[TAPROOT_CONTROL_BASE_SIZE..].chunks_exact(TAPROOT_CONTROL_NODE_SIZE);
+        if !nodes.remainder().is_empty() || nodes.len() > TAPROOT_CONTROL_MAX_NODE_COUNT {
+            return Err("Invalid control block size");
+        }
+        let internal_key = PublicKey::from_xonly(&ser[1..TAPROOT_CONTROL_BASE_SIZE])?;
+        let merkle_branch = nodes
+            .map(|chunk| {
+                let mut node = [0u8; 32];
+                node.copy_from_slice(chunk);
+                node
+            })
+            .collect();
+        Ok(ControlBlock { leaf_version: ser[0] & 0xfe, output_key_parity: ser[0] & 1 == 1, internal_key, merkle_branch })
+    }
+
+    // Recompute the merkle root from the script and the branch, and check the output key commits to it.
+    pub fn verify_taproot_commitment(&self, output_key: &[u8; 32], script: &Script) -> bool {
+        let leaf = TapLeaf { script: script.clone(), version: self.leaf_version };
+        let root = self.merkle_branch.iter().fold(leaf.leaf_hash(), |node, sibling| tap_branch_hash(&node, sibling));
+        self.internal_key.verify_tap_commitment(output_key, self.output_key_parity, Some(&root))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utility::hex_to_bytes;
+    use std::convert::TryInto;
+
+    fn hex32(hex: &str) -> [u8; 32] {
+        hex_to_bytes(hex)[..].try_into().unwrap()
+    }
+
+    // (internal key, merkle root, tweak, output key) from BIP341's scriptPubKey vectors.
+    const OUTPUT_KEY_VECTORS: [(&str, &str, &str, &str); 3] = [
+        (
+            "d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d",
+            "",
+            "b86e7be8f39bab32a6f2c0443abbc210f0edac0e2c53d501b36b64437d9c6c70",
+            "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343",
+        ),
+        (
+            "187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+            "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21",
+            "cbd8679ba636c1110ea247542cfbd964131a6be84f873f7f3b62a777528ed001",
+            "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3",
+        ),
+        (
+            "e0dfe2300b0dd746a3f8674dfd4525623639042569d829c7f0eed9602d263e6f",
+            "ccbd66c6f7e8fdab47b3a486f59d28262be857f30d4773f2d5ea47f7761ce0e2",
+            "b57bfa183d28eeb6ad688ddaabb265b4a41fbf68e5fed2c72c74de70d5a786f4",
+            "91b64d5324723a985170e4dc5a0f84c041804f2cd12660fa5dec09fc21783605",
+        ),
+    ];
+
+    // (internal private key, merkle root, tweaked private key, sighash, signature) from BIP341's keyPathSpending vectors.
+    const KEY_PATH_VECTORS: [(&str, &str, &str, &str, &str); 3] = [
+        (
+            "6b973d88838f27366ed61c9ad6367663045cb456e28335c109e30717ae0c6baa",
+            "",
+            "2405b971772ad26915c8dcdf10f238753a9b837e5f8e6a86fd7c0cce5b7296d9",
+            "2514a6272f85cfa0f45eb907fcb0d121b808ed37c6ea160a5a9046ed5526d555",
+            "ed7c1647cb97379e76892be0cacff57ec4a7102aa24296ca39af7541246d8ff14d38958d4cc1e2e478e4d4a764bbfd835b16d4e314b72937b29833060b87276c",
+        ),
+        (
+            "1e4da49f6aaf4e5cd175fe08a32bb5cb4863d963921255f33d3bc31e1343907f",
+            "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21",
+            "ea260c3b10e60f6de018455cd0278f2f5b7e454be1999572789e6a9565d26080",
+            "325a644af47e8a5a2591cda0ab0723978537318f10e6a63d4eed783b96a71a4d",
+            "052aedffc554b41f52b521071793a6b88d6dbca9dba94cf34c83696de0c1ec35ca9c5ed4ab28059bd606a4f3a657eec0bb96661d42921b5f50a95ad33675b54f",
+        ),
+        (
+            "f36bb07a11e469ce941d16b63b11b9b9120a84d9d87cff2c84a8d4affb438f4e",
+            "ccbd66c6f7e8fdab47b3a486f59d28262be857f30d4773f2d5ea47f7761ce0e2",
+            "a8e7aa924f0d58854185a490e6c41f6efb7b675c0f3331b7f14b549400b4d501",
+            "4f900a0bae3f1446fd48490c2958b5a023228f01661cda3496a11da502a7f7ef",
+            "b4010dd48a617db09926f729e79c33ae0b4e94b79f04a1ae93ede6315eb3669de185a17d2b0ac9ee09fd4c64b678a0b61a0a86fa888a273c8511be83bfd6810f",
+        ),
+    ];
+
+    #[test]
+    fn test_bip341_output_keys() {
+        for (internal, root, tweak, output) in &OUTPUT_KEY_VECTORS {
+            let internal = PublicKey::from_xonly(&hex_to_bytes(internal)).unwrap();
+            let root = if root.is_empty() { None } else { Some(hex32(root)) };
+            assert_eq!(tap_tweak_hash(&internal, root.as_ref()).0, hex32(tweak));
+
+            let output_key = internal.tap_tweak(root.as_ref()).unwrap();
+            assert_eq!(output_key.x_only(), hex32(output));
+
+            let parity = !output_key.has_even_y();
+            assert!(internal.verify_tap_commitment(&hex32(output), parity, root.as_ref()));
+            assert!(!internal.verify_tap_commitment(&hex32(output), !parity, root.as_ref()));
+            assert!(!internal.verify_tap_commitment(&hex32(output), parity, Some(&[1u8; 32])));
+        }
+    }
+
+    #[test]
+    fn test_bip341_key_path_signing() {
+        for (internal, root, tweaked, sighash, sig) in &KEY_PATH_VECTORS {
+            let privkey = PrivateKey::from_serialized(&hex_to_bytes(internal));
+            let root = if root.is_empty() { None } else { Some(hex32(root)) };
+            assert_eq!(privkey.tap_tweak(root.as_ref()).unwrap().serialize(), hex32(tweaked));
+
+            let signature = privkey.sign_taproot(&hex_to_bytes(sighash), root.as_ref()).unwrap();
+            assert_eq!(signature.serialize()[..], hex_to_bytes(sig)[..]);
+
+            let output_key = privkey.generate_pubkey().tap_tweak(root.as_ref()).unwrap();
+            assert!(output_key.verify_schnorr_msg(&hex_to_bytes(sighash), signature));
+        }
+    }
+
+    struct ScriptTreeVector {
+        internal_key: &'static str,
+        // (depth, leaf version, script) in depth first order.
+        leaves: &'static [(u8, u8, &'static str)],
+        leaf_hashes: &'static [&'static str],
+        merkle_root: &'static str,
+        output_key: &'static str,
+        control_blocks: &'static [&'static str],
+    }
+
+    // The script trees from BIP341's scriptPubKey vectors.
+    const SCRIPT_TREE_VECTORS: [ScriptTreeVector; 6] = [
+        ScriptTreeVector {
+            internal_key: "187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+            leaves: &[
+                (0, 0xc0, "20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac"),
+            ],
+            leaf_hashes: &[
+                "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21",
+            ],
+            merkle_root: "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21",
+            output_key: "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3",
+            control_blocks: &[
+                "c1187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+            ],
+        },
+        ScriptTreeVector {
+            internal_key: "93478e9488f956df2396be2ce6c5cced75f900dfa18e7dabd2428aae78451820",
+            leaves: &[
+                (0, 0xc0, "20b617298552a72ade070667e86ca63b8f5789a9fe8731ef91202a91c9f3459007ac"),
+            ],
+            leaf_hashes: &[
+                "c525714a7f49c28aedbbba78c005931a81c234b2f6c99a73e4d06082adc8bf2b",
+            ],
+            merkle_root: "c525714a7f49c28aedbbba78c005931a81c234b2f6c99a73e4d06082adc8bf2b",
+            output_key: "e4d810fd50586274face62b8a807eb9719cef49c04177cc6b76a9a4251d5450e",
+            control_blocks: &[
+                "c093478e9488f956df2396be2ce6c5cced75f900dfa18e7dabd2428aae78451820",
+            ],
+        },
+        ScriptTreeVector {
+            internal_key: "ee4fe085983462a184015d1f782d6a5f8b9c2b60130aff050ce221ecf3786592",
+            leaves: &[
+                (1, 0xc0, "20387671353e273264c495656e27e39ba899ea8fee3bb69fb2a680e22093447d48ac"),
+                (1, 0xfa, "06424950333431"),
+            ],
+            leaf_hashes: &[
+                "8ad69ec7cf41c2a4001fd1f738bf1e505ce2277acdcaa63fe4765192497f47a7",
+                "f224a923cd0021ab202ab139cc56802ddb92dcfc172b9212261a539df79a112a",
+            ],
+            merkle_root: "6c2dc106ab816b73f9d07e3cd1ef2c8c1256f519748e0813e4edd2405d277bef",
+            output_key: "712447206d7a5238acc7ff53fbe94a3b64539ad291c7cdbc490b7577e4b17df5",
+            control_blocks: &[
+                "c0ee4fe085983462a184015d1f782d6a5f8b9c2b60130aff050ce221ecf3786592f224a923cd0021ab202ab139cc56802ddb92dcfc172b9212261a539df79a112a",
+                "faee4fe085983462a184015d1f782d6a5f8b9c2b60130aff050ce221ecf37865928ad69ec7cf41c2a4001fd1f738bf1e505ce2277acdcaa63fe4765192497f47a7",
+            ],
+        },
+        ScriptTreeVector {
+            internal_key: "f9f400803e683727b14f463836e1e78e1c64417638aa066919291a225f0e8dd8",
+            leaves: &[
+                (1, 0xc0, "2044b178d64c32c4a05cc4f4d1407268f764c940d20ce97abfd44db5c3592b72fdac"),
+                (1, 0xc0, "07546170726f6f74"),
+            ],
+            leaf_hashes: &[
+                "64512fecdb5afa04f98839b50e6f0cb7b1e539bf6f205f67934083cdcc3c8d89",
+                "2cb2b90daa543b544161530c925f285b06196940d6085ca9474d41dc3822c5cb",
+            ],
+            merkle_root: "ab179431c28d3b68fb798957faf5497d69c883c6fb1e1cd9f81483d87bac90cc",
+            output_key: "77e30a5522dd9f894c3f8b8bd4c4b2cf82ca7da8a3ea6a239655c39c050ab220",
+            control_blocks: &[
+                "c1f9f400803e683727b14f463836e1e78e1c64417638aa066919291a225f0e8dd82cb2b90daa543b544161530c925f285b06196940d6085ca9474d41dc3822c5cb",
+                "c1f9f400803e683727b14f463836e1e78e1c64417638aa066919291a225f0e8dd864512fecdb5afa04f98839b50e6f0cb7b1e539bf6f205f67934083cdcc3c8d89",
+            ],
+        },
+        ScriptTreeVector {
+            internal_key: "e0dfe2300b0dd746a3f8674dfd4525623639042569d829c7f0eed9602d263e6f",
+            leaves: &[
+                (1, 0xc0, "2072ea6adcf1d371dea8fba1035a09f3d24ed5a059799bae114084130ee5898e69ac"),
+                (2, 0xc0, "202352d137f2f3ab38d1eaa976758873377fa5ebb817372c71e2c542313d4abda8ac"),
+                (2, 0xc0, "207337c0dd4253cb86f2c43a2351aadd82cccb12a172cd120452b9bb8324f2186aac"),
+            ],
+            leaf_hashes: &[
+                "2645a02e0aac1fe69d69755733a9b7621b694bb5b5cde2bbfc94066ed62b9817",
+                "ba982a91d4fc552163cb1c0da03676102d5b7a014304c01f0c77b2b8e888de1c",
+                "9e31407bffa15fefbf5090b149d53959ecdf3f62b1246780238c24501d5ceaf6",
+            ],
+            merkle_root: "ccbd66c6f7e8fdab47b3a486f59d28262be857f30d4773f2d5ea47f7761ce0e2",
+            output_key: "91b64d5324723a985170e4dc5a0f84c041804f2cd12660fa5dec09fc21783605",
+            control_blocks: &[
+                "c0e0dfe2300b0dd746a3f8674dfd4525623639042569d829c7f0eed9602d263e6fffe578e9ea769027e4f5a3de40732f75a88a6353a09d767ddeb66accef85e553",
+                "c0e0dfe2300b0dd746a3f8674dfd4525623639042569d829c7f0eed9602d263e6f9e31407bffa15fefbf5090b149d53959ecdf3f62b1246780238c24501d5ceaf62645a02e0aac1fe69d69755733a9b7621b694bb5b5cde2bbfc94066ed62b9817",
+                "c0e0dfe2300b0dd746a3f8674dfd4525623639042569d829c7f0eed9602d263e6fba982a91d4fc552163cb1c0da03676102d5b7a014304c01f0c77b2b8e888de1c2645a02e0aac1fe69d69755733a9b7621b694bb5b5cde2bbfc94066ed62b9817",
+            ],
+        },
+        ScriptTreeVector {
+            internal_key: "55adf4e8967fbd2e29f20ac896e60c3b0f1d5b0efa9d34941b5958c7b0a0312d",
+            leaves: &[
+                (1, 0xc0, "2071981521ad9fc9036687364118fb6ccd2035b96a423c59c5430e98310a11abe2ac"),
+                (2, 0xc0, "20d5094d2dbe9b76e2c245a2b89b6006888952e2faa6a149ae318d69e520617748ac"),
+                (2, 0xc0, "20c440b462ad48c7a77f94cd4532d8f2119dcebbd7c9764557e62726419b08ad4cac"),
+            ],
+            leaf_hashes: &[
+                "f154e8e8e17c31d3462d7132589ed29353c6fafdb884c5a6e04ea938834f0d9d",
+                "737ed1fe30bc42b8022d717b44f0d93516617af64a64753b7a06bf16b26cd711",
+                "d7485025fceb78b9ed667db36ed8b8dc7b1f0b307ac167fa516fe4352b9f4ef7",
+            ],
+            merkle_root: "2f6b2c5397b6d68ca18e09a3f05161668ffe93a988582d55c6f07bd5b3329def",
+            output_key: "75169f4001aa68f15bbed28b218df1d0a62cbbcf1188c6665110c293c907b831",
+            control_blocks: &[
+                "c155adf4e8967fbd2e29f20ac896e60c3b0f1d5b0efa9d34941b5958c7b0a0312d3cd369a528b326bc9d2133cbd2ac21451acb31681a410434672c8e34fe757e91",
+                "c155adf4e8967fbd2e29f20ac896e60c3b0f1d5b0efa9d34941b5958c7b0a0312dd7485025fceb78b9ed667db36ed8b8dc7b1f0b307ac167fa516fe4352b9f4ef7f154e8e8e17c31d3462d7132589ed29353c6fafdb884c5a6e04ea938834f0d9d",
+                "c155adf4e8967fbd2e29f20ac896e60c3b0f1d5b0efa9d34941b5958c7b0a0312d737ed1fe30bc42b8022d717b44f0d93516617af64a64753b7a06bf16b26cd711f154e8e8e17c31d3462d7132589ed29353c6fafdb884c5a6e04ea938834f0d9d",
+            ],
+        },
+    ];
+
+    fn leaf(version: u8, script: &str) -> TapLeaf {
+        TapLeaf::with_version(Script::from(hex_to_bytes(script)), version).unwrap()
+    }
+
+    #[test]
+    fn test_bip341_script_trees() {
+        for vector in &SCRIPT_TREE_VECTORS {
+            let internal = PublicKey::from_xonly(&hex_to_bytes(vector.internal_key)).unwrap();
+            let mut builder = TaprootBuilder::new();
+            for (i, (depth, version, script)) in vector.leaves.iter().enumerate() {
+                assert!(!builder.is_finalizable());
+                let leaf = leaf(*version, script);
+                assert_eq!(leaf.leaf_hash(), hex32(vector.leaf_hashes[i]));
+                builder = builder.add_leaf_with_version(*depth, leaf).unwrap();
+            }
+            let spend_info = builder.finalize(&internal).unwrap();
+            assert_eq!(spend_info.merkle_root, Some(hex32(vector.merkle_root)));
+            assert_eq!(spend_info.output_key.x_only(), hex32(vector.output_key));
+            assert_eq!(spend_info.leaves().count(), vector.leaves.len());
+
+            for (i, (_, version, script)) in vector.leaves.iter().enumerate() {
+                let control_block = spend_info.control_block(&leaf(*version, script)).unwrap();
+                assert_eq!(control_block.serialize(), hex_to_bytes(vector.control_blocks[i]));
+                assert_eq!(ControlBlock::parse(&control_block.serialize()).unwrap(), control_block);
+
+                let script = Script::from(hex_to_bytes(script));
+                assert!(control_block.verify_taproot_commitment(&hex32(vector.output_key), &script));
+                assert!(!control_block.verify_taproot_commitment(&hex32(vector.internal_key), &script));
+                assert!(!control_block.verify_taproot_commitment(&hex32(vector.output_key), &Script::new()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_huffman_tree() {
+        let internal = PrivateKey::from_serialized(&[7u8; 32]).generate_pubkey();
+        let leaves: Vec<_> = (0..5u8).map(|i| TapLeaf::new(Script::from(vec![0x51 + i]))).collect();
+        let weights = [1, 1, 2, 4, 8];
+        let builder = TaprootBuilder::with_huffman_tree(weights.iter().cloned().zip(leaves.iter().cloned()).collect()).unwrap();
+        let spend_info = builder.finalize(&internal).unwrap();
+        let output_key = spend_info.output_key.x_only();
+
+        let depths: Vec<_> = leaves.iter().map(|leaf| spend_info.control_block(leaf).unwrap().merkle_branch.len()).collect();
+        assert_eq!(depths, [4, 4, 3, 2, 1]);
+        for leaf in &leaves {
+            let control_block = spend_info.control_block(leaf).unwrap();
+            assert!(control_block.verify_taproot_commitment(&output_key, &leaf.script));
+        }
+        assert!(spend_info.control_block(&TapLeaf::new(Script::new())).is_none());
+        assert!(TaprootBuilder::with_huffman_tree(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        let script = || Script::from(vec![0x51]);
+        // Not depth first, the depth 2 leaf is missing its sibling.
+        let builder = TaprootBuilder::new().add_leaf(2, script()).unwrap();
+        assert!(builder.add_leaf(1, script()).is_err());
+        // Incomplete tree.
+        let builder = TaprootBuilder::new().add_leaf(1, script()).unwrap();
+        assert!(builder.finalize(&PublicKey::from_xonly(&[1u8; 32]).unwrap()).is_err());
+        // Over complete tree.
+        let builder = TaprootBuilder::new().add_leaf(0, script()).unwrap();
+        assert!(builder.add_leaf(0, script()).is_err());
+        let builder = TaprootBuilder::new().add_leaf(1, script()).unwrap().add_leaf(1, script()).unwrap();
+        assert_eq!(builder.add_leaf(1, script()).err(), Some("Script tree is already complete"));
+        let builder = TaprootBuilder::new().add_leaf(1, script()).unwrap().add_leaf(1, script()).unwrap();
+        assert!(builder.add_leaf(3, script()).is_err());
+        assert!(TaprootBuilder::new().add_leaf(129, script()).is_err());
+        assert!(TapLeaf::with_version(script(), 0xc1).is_err());
+        assert!(TapLeaf::with_version(script(), 0x50).is_err());
+
+        assert!(ControlBlock::parse(&[0xc0; 32]).is_err());
+        assert!(ControlBlock::parse(&[0xc0; 34]).is_err());
+    }
+
+    #[test]
+    fn test_key_path_only() {
+        let internal = PublicKey::from_xonly(&hex_to_bytes(OUTPUT_KEY_VECTORS[0].0)).unwrap();
+        let spend_info = TaprootSpendInfo::key_path_only(&internal).unwrap();
+        assert_eq!(spend_info.merkle_root, None);
+        assert_eq!(spend_info.output_key.x_only(), hex32(OUTPUT_KEY_VECTORS[0].3));
+        assert_eq!(spend_info.leaves().count(), 0);
+    }
+}
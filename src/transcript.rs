@@ -0,0 +1,81 @@
+use crate::secp256k1::{get_context, PublicKey};
+use crate::utility::{int_from_bytes, int_to_bytes, tagged_hash};
+use rug::Integer;
+
+// A Fiat-Shamir transcript. Everything the prover sends is absorbed in order, and challenges are squeezed from
+// the whole history, so a challenge can't be reused in another protocol or with other public inputs.
+// The state is a chain of tagged hashes: every absorbed item is framed by its label and length,
+// so no two different sequences of items give the same state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transcript {
+    state: [u8; 32],
+}
+
+impl Transcript {
+    // `domain` separates protocols, every protocol should use its own.
+    pub fn new(domain: &[u8]) -> Transcript {
+        Transcript { state: tagged_hash(b"Transcript/domain", domain) }
+    }
+
+    pub fn append_message(&mut self, label: &[u8], msg: &[u8]) {
+        let framed = [&self.state[..], &(label.len() as u64).to_be_bytes(), label, &(msg.len() as u64).to_be_bytes(), msg].concat();
+        self.state = tagged_hash(b"Transcript/append", &framed);
+    }
+
+    pub fn append_point(&mut self, label: &[u8], point: &PublicKey) {
+        self.append_message(label, &point.compressed());
+    }
+
+    pub fn append_scalar(&mut self, label: &[u8], scalar: &Integer) {
+        self.append_message(label, &int_to_bytes(scalar));
+    }
+
+    // 32 challenge bytes, the challenge is absorbed too, so the next one is different.
+    pub fn challenge_bytes(&mut self, label: &[u8]) -> [u8; 32] {
+        let challenge = tagged_hash(b"Transcript/challenge", &[&self.state[..], label].concat());
+        self.append_message(label, &challenge);
+        challenge
+    }
+
+    // A challenge mod n.
+    pub fn challenge_scalar(&mut self, label: &[u8]) -> Integer {
+        int_from_bytes(&self.challenge_bytes(label)) % &get_context().order
+    }
+
+    // Bind secret data to the current state, e.g. to derive nonces that depend on everything absorbed so far.
+    pub(crate) fn state(&self) -> [u8; 32] {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secp256k1::PrivateKey;
+
+    #[test]
+    fn test_transcript() {
+        let point = PrivateKey::from_serialized(&[1; 32]).generate_pubkey();
+        let transcript = |domain: &[u8], items: &[(&[u8], &[u8])]| {
+            let mut t = Transcript::new(domain);
+            items.iter().for_each(|(label, msg)| t.append_message(label, msg));
+            t.challenge_bytes(b"c")
+        };
+        let base = transcript(b"test", &[(b"a", b"bc")]);
+        assert_eq!(base, transcript(b"test", &[(b"a", b"bc")]));
+        assert_ne!(base, transcript(b"other", &[(b"a", b"bc")]));
+        // The framing keeps items apart.
+        assert_ne!(base, transcript(b"test", &[(b"ab", b"c")]));
+        assert_ne!(base, transcript(b"test", &[(b"a", b"b"), (b"", b"c")]));
+        assert_ne!(base, transcript(b"test", &[]));
+
+        let mut t = Transcript::new(b"test");
+        t.append_point(b"P", &point);
+        t.append_scalar(b"x", &Integer::from(5));
+        let mut fork = t.clone();
+        let (c1, c2) = (t.challenge_scalar(b"e"), t.challenge_scalar(b"e"));
+        assert_ne!(c1, c2);
+        assert!(c1 < get_context().order);
+        assert_eq!(fork.challenge_scalar(b"e"), c1);
+    }
+}
@@ -1,4 +1,8 @@
 use crate::hash::HashTrait;
+use crate::secp256k1::{get_context, PrivateKey};
+use rug::{integer::Order, Integer};
+use std::ptr;
+use std::sync::atomic::{self, Ordering};
 
 #[allow(dead_code)]
 pub fn bytes_to_hex(bytes: &[u8]) -> String {
@@ -36,6 +40,47 @@ pub fn hex_to_bytes(hex_asm: &str) -> Vec<u8> {
     bytes
 }
 
+pub(crate) fn int_from_bytes(bytes: &[u8]) -> Integer {
+    Integer::from_digits(bytes, Order::MsfBe)
+}
+
+pub(crate) fn int_to_bytes(num: &Integer) -> [u8; 32] {
+    PrivateKey::new(num.clone()).serialize()
+}
+
+// `len` pseudo random bytes from a seed: hash_tag(seed || 0) || hash_tag(seed || 1) || ... (4 byte big endian counter).
+pub(crate) fn expand_seed(tag: &[u8], seed: &[u8], len: usize) -> Vec<u8> {
+    (0u32..).flat_map(|i| tagged_hash(tag, &[seed, &i.to_be_bytes()].concat()).to_vec()).take(len).collect()
+}
+
+// An integer in [0, bound) derived from a seed, the 16 bytes more than the bound make the bias negligible.
+pub(crate) fn int_from_seed(tag: &[u8], seed: &[u8], bound: &Integer) -> Integer {
+    int_from_bytes(&expand_seed(tag, seed, bound.significant_bits() as usize / 8 + 16)) % bound
+}
+
+// A scalar in [1, n-1] derived from a seed.
+pub(crate) fn scalar_from_seed(tag: &[u8], seed: &[u8]) -> Integer {
+    int_from_seed(tag, seed, &Integer::from(&get_context().order - 1)) + 1
+}
+
+// A 32 byte big endian scalar in [0, n-1].
+pub(crate) fn parse_scalar(ser: &[u8]) -> Result<Integer, &'static str> {
+    let scalar = int_from_bytes(ser);
+    if scalar >= get_context().order {
+        return Err("The scalar overflows the curve order");
+    }
+    Ok(scalar)
+}
+
+// Overwrite secret bytes before the memory is freed.
+pub(crate) fn zeroize(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // Volatile so the compiler can't optimize away writes to memory that is about to be freed.
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
 
 #[cfg(test)]
 mod test {
@@ -2,7 +2,7 @@
 
 extern crate secp256k1 as test_secp256k1;
 
-use ecc_secp256k1::{HashTrait, PrivateKey, PublicKey, Signature};
+use ecc_secp256k1::{HashTrait, PrivateKey, PublicKey, Scalar, Signature};
 use test_secp256k1::rand::{thread_rng, Rng};
 use test_secp256k1::{
     rand::thread_rng as TestRng, Message as TestMessage, PublicKey as TestPublicKey, Secp256k1 as TestSecp256k1,
@@ -108,3 +108,44 @@ fn test_compare_sigs() {
 
     assert_eq!(&my_sig[..], &test_sig[..]);
 }
+
+#[test]
+fn test_cmp_tweaks() {
+    let secp = TestSecp256k1::new();
+    let mut rng = thread_rng();
+    let tweak: [u8; 32] = rng.gen();
+
+    let test_privkey = TestPrivateKey::new(&mut TestRng());
+    let test_pubkey = TestPublicKey::from_secret_key(&secp, &test_privkey);
+    let my_privkey = PrivateKey::from_serialized(&test_privkey[..]);
+    let my_pubkey = PublicKey::from_compressed(&test_pubkey.serialize()).unwrap();
+
+    let mut test_added = test_privkey;
+    let mut test_multiplied = test_privkey;
+    assert_eq!(test_added.add_assign(&tweak).is_ok(), my_privkey.add_tweak(&Scalar(tweak)).is_ok());
+    assert_eq!(test_multiplied.mul_assign(&tweak).is_ok(), my_privkey.mul_tweak(&Scalar(tweak)).is_ok());
+    if let Ok(added) = my_privkey.add_tweak(&Scalar(tweak)) {
+        assert_eq!(&added.serialize()[..], &test_added[..]);
+    }
+    if let Ok(multiplied) = my_privkey.mul_tweak(&Scalar(tweak)) {
+        assert_eq!(&multiplied.serialize()[..], &test_multiplied[..]);
+    }
+
+    let mut test_added = test_pubkey;
+    let mut test_multiplied = test_pubkey;
+    assert_eq!(test_added.add_exp_assign(&secp, &tweak).is_ok(), my_pubkey.add_exp_tweak(&Scalar(tweak)).is_ok());
+    assert_eq!(test_multiplied.mul_assign(&secp, &tweak).is_ok(), my_pubkey.mul_tweak(&Scalar(tweak)).is_ok());
+    if let Ok(added) = my_pubkey.add_exp_tweak(&Scalar(tweak)) {
+        assert_eq!(&added.compressed()[..], &test_added.serialize()[..]);
+    }
+    if let Ok(multiplied) = my_pubkey.mul_tweak(&Scalar(tweak)) {
+        assert_eq!(&multiplied.compressed()[..], &test_multiplied.serialize()[..]);
+    }
+
+    // An overflowing tweak is rejected by both.
+    let overflow = [0xFF; 32];
+    assert!(test_privkey.clone().add_assign(&overflow).is_err());
+    assert!(my_privkey.add_tweak(&Scalar(overflow)).is_err());
+    assert!(test_pubkey.clone().mul_assign(&secp, &overflow).is_err());
+    assert!(my_pubkey.mul_tweak(&Scalar(overflow)).is_err());
+}